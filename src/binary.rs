@@ -0,0 +1,248 @@
+use std::error::Error;
+
+use object::{
+    macho,
+    read::{
+        archive::ArchiveFile,
+        macho::{FatArch, FatHeader},
+    },
+    Architecture, FileKind, Object,
+};
+
+use crate::import_library;
+
+/// A single object inside a file, e.g. one architecture of a universal binary.
+pub struct Slice<'data> {
+    /// The architecture or member name of the slice, only set for files that contain several objects.
+    pub name: Option<String>,
+    pub data: &'data [u8],
+}
+
+/// Splits the file into the objects it contains.
+///
+/// Universal (fat) Mach-O files are split into one slice per architecture and static
+/// libraries into one slice per member, every other file is a single slice.
+/// When `arch` is given, only the slices for that architecture are returned.
+pub fn slices<'data>(
+    binary_data: &'data [u8],
+    arch: Option<&str>,
+) -> Result<Vec<Slice<'data>>, Box<dyn Error>> {
+    let slices = match FileKind::parse(binary_data)? {
+        FileKind::MachOFat32 => {
+            fat_slices(FatHeader::parse_arch32(binary_data)?, binary_data, arch)?
+        }
+        FileKind::MachOFat64 => {
+            fat_slices(FatHeader::parse_arch64(binary_data)?, binary_data, arch)?
+        }
+        FileKind::Archive if !import_library::is_import_library(binary_data) => {
+            archive_slices(binary_data, None, arch)?
+        }
+        _ => {
+            // A thin file contains only the slice for its own architecture.
+            if let Some(arch) = arch {
+                let architecture = architecture(binary_data)?;
+                if !is_same_arch(&architecture_name(architecture), arch) {
+                    return Err(format!("the file does not contain architecture {}", arch).into());
+                }
+            }
+
+            return Ok(vec![Slice {
+                name: None,
+                data: binary_data,
+            }]);
+        }
+    };
+
+    if let Some(arch) = arch {
+        if slices.is_empty() {
+            return Err(format!("the file does not contain architecture {}", arch).into());
+        }
+    }
+
+    Ok(slices)
+}
+
+fn fat_slices<'data, Fat: FatArch>(
+    arches: &[Fat],
+    binary_data: &'data [u8],
+    arch: Option<&str>,
+) -> Result<Vec<Slice<'data>>, Box<dyn Error>> {
+    let mut slices = Vec::<Slice>::new();
+    for fat_arch in arches {
+        let subtype = fat_arch.cpusubtype() & !macho::CPU_SUBTYPE_MASK;
+        let name = match (fat_arch.cputype(), subtype) {
+            (macho::CPU_TYPE_ARM64, macho::CPU_SUBTYPE_ARM64E) => "arm64e".to_owned(),
+            (macho::CPU_TYPE_X86_64, macho::CPU_SUBTYPE_X86_64_H) => "x86_64h".to_owned(),
+            _ => architecture_name(fat_arch.architecture()),
+        };
+        if arch.is_some_and(|arch| !is_same_arch(&name, arch)) {
+            continue;
+        }
+
+        // Universal static libraries contain one archive per architecture.
+        let data = fat_arch.data(binary_data)?;
+        if FileKind::parse(data)? == FileKind::Archive {
+            slices.extend(archive_slices(data, Some(&name), None)?);
+            continue;
+        }
+
+        slices.push(Slice {
+            name: Some(name),
+            data,
+        });
+    }
+
+    Ok(slices)
+}
+
+/// Splits a static library into its members, which are named after the member file.
+///
+/// Members that are not object files, e.g. LLVM bitcode, are skipped.
+fn archive_slices<'data>(
+    binary_data: &'data [u8],
+    prefix: Option<&str>,
+    arch: Option<&str>,
+) -> Result<Vec<Slice<'data>>, Box<dyn Error>> {
+    let archive = ArchiveFile::parse(binary_data)?;
+
+    let mut slices = Vec::<Slice>::new();
+    for member in archive.members() {
+        let member = member?;
+        let data = member.data(binary_data)?;
+
+        let Ok(object_file) = object::File::parse(data) else {
+            continue;
+        };
+        if arch
+            .is_some_and(|arch| !is_same_arch(&architecture_name(object_file.architecture()), arch))
+        {
+            continue;
+        }
+
+        let member_name = String::from_utf8_lossy(member.name());
+        let name = match prefix {
+            Some(prefix) => format!("{} ({})", member_name, prefix),
+            None => member_name.into_owned(),
+        };
+
+        slices.push(Slice {
+            name: Some(name),
+            data,
+        });
+    }
+
+    Ok(slices)
+}
+
+/// Returns the slice of the file that was built for the given architecture.
+///
+/// This is how a loader picks the matching part of a universal binary.
+pub fn slice_for(binary_data: &[u8], architecture: Architecture) -> Option<&[u8]> {
+    let arches: Vec<(Architecture, &[u8])> = match FileKind::parse(binary_data).ok()? {
+        FileKind::MachOFat32 => FatHeader::parse_arch32(binary_data)
+            .ok()?
+            .iter()
+            .filter_map(|arch| Some((arch.architecture(), arch.data(binary_data).ok()?)))
+            .collect(),
+        FileKind::MachOFat64 => FatHeader::parse_arch64(binary_data)
+            .ok()?
+            .iter()
+            .filter_map(|arch| Some((arch.architecture(), arch.data(binary_data).ok()?)))
+            .collect(),
+        _ => vec![(
+            object::File::parse(binary_data).ok()?.architecture(),
+            binary_data,
+        )],
+    };
+
+    arches
+        .into_iter()
+        .find(|(slice_architecture, _)| *slice_architecture == architecture)
+        .map(|(_, data)| data)
+}
+
+/// Returns the architecture of a thin file.
+fn architecture(binary_data: &[u8]) -> Result<Architecture, Box<dyn Error>> {
+    // Import libraries are archives, their members name the architecture.
+    if let Some(member) = import_library::members(binary_data)?.first() {
+        return Ok(member.architecture);
+    }
+
+    Ok(object::File::parse(binary_data)?.architecture())
+}
+
+/// The name of the architecture, following the Mach-O naming conventions.
+pub fn architecture_name(architecture: Architecture) -> String {
+    let name = match architecture {
+        Architecture::Aarch64 => "arm64",
+        Architecture::Aarch64_Ilp32 => "arm64_32",
+        Architecture::Arm => "arm",
+        Architecture::I386 => "i386",
+        Architecture::PowerPc => "ppc",
+        Architecture::PowerPc64 => "ppc64",
+        Architecture::X86_64 => "x86_64",
+        _ => return format!("{:?}", architecture).to_lowercase(),
+    };
+
+    name.to_owned()
+}
+
+/// Compares architecture names, accepting the common aliases, e.g. aarch64 for arm64.
+fn is_same_arch(name: &str, arch: &str) -> bool {
+    fn canonical(name: &str) -> String {
+        match name.to_lowercase().as_str() {
+            "aarch64" => "arm64".to_owned(),
+            "amd64" | "x64" => "x86_64".to_owned(),
+            "x86" | "i686" => "i386".to_owned(),
+            other => other.to_owned(),
+        }
+    }
+
+    canonical(name) == canonical(arch)
+}
+
+/// A slice of the first and the matching slice of the second file.
+pub type SlicePair<'a, 'b> = (Option<String>, Option<&'a [u8]>, Option<&'b [u8]>);
+
+/// Matches the slices of two files by their architecture.
+///
+/// Two thin files are always paired with each other. Otherwise thin files are named after
+/// their architecture, and slices without a counterpart are paired with `None`.
+pub fn pair_slices<'a, 'b>(
+    first: Vec<Slice<'a>>,
+    second: Vec<Slice<'b>>,
+) -> Result<Vec<SlicePair<'a, 'b>>, Box<dyn Error>> {
+    if let ([first], [second]) = (first.as_slice(), second.as_slice()) {
+        if first.name.is_none() && second.name.is_none() {
+            return Ok(vec![(None, Some(first.data), Some(second.data))]);
+        }
+    }
+
+    let name = |slice: &Slice| -> Result<String, Box<dyn Error>> {
+        match &slice.name {
+            Some(name) => Ok(name.clone()),
+            None => Ok(architecture_name(architecture(slice.data)?)),
+        }
+    };
+
+    let mut second: Vec<(String, &'b [u8])> = second
+        .iter()
+        .map(|slice| Ok((name(slice)?, slice.data)))
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    let mut pairs = Vec::<SlicePair>::new();
+    for slice in &first {
+        let slice_name = name(slice)?;
+        let counterpart = second
+            .iter()
+            .position(|(name, _)| is_same_arch(name, &slice_name))
+            .map(|index| second.remove(index).1);
+
+        pairs.push((Some(slice_name), Some(slice.data), counterpart));
+    }
+    for (name, data) in second {
+        pairs.push((Some(name), None, Some(data)));
+    }
+
+    Ok(pairs)
+}
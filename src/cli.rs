@@ -1,4 +1,6 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
 
 use crate::{cmd::*, output};
 
@@ -12,14 +14,58 @@ pub struct Cli {
     /// Selects the output format.
     #[arg(long, default_value_t = output::OutputType::Plain, value_enum)]
     pub output: output::OutputType,
+
+    /// Options that apply to every command.
+    #[command(flatten)]
+    pub global: GlobalOptions,
+}
+
+#[derive(Args)]
+pub struct GlobalOptions {
+    /// Selects one architecture of a universal binary, e.g. x86_64 or arm64.
+    #[arg(long, global = true)]
+    pub arch: Option<String>,
+
+    /// Maps Windows API sets to their host DLLs, using the schema of this apisetschema.dll.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub api_set_schema: Option<PathBuf>,
+
+    /// Searches PE dependencies in the Windows installation described by this JSON file.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub windows_config: Option<PathBuf>,
+
+    /// Searches dependencies in this root file system instead of the host, e.g. an ARM image.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub sysroot: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Compares the imports and exports of two binaries.
+    Compare(compare::Arguments),
+
     /// Displays information, such as architecture of a binary.
     Info(info::Arguments),
 
     /// List different parts of a given binary.
     #[clap(subcommand)]
     List(list::Commands),
+
+    /// Finds the libraries that export a symbol.
+    Provides(provides::Arguments),
+
+    /// Lists the binaries in a directory that load a library.
+    Rdeps(rdeps::Arguments),
+
+    /// Displays the libraries a binary loads as a tree.
+    Tree(tree::Arguments),
+
+    /// Lists the dependencies a binary imports no symbol from.
+    UnusedDeps(unused_deps::Arguments),
+
+    /// Verifies that a library exports everything a binary imports from it.
+    Verify(verify::Arguments),
+
+    /// Shows the chains of libraries that make a binary load a library.
+    Why(why::Arguments),
 }
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Difference<T> {
+    Added(T),
+    Removed(T),
+    Changed { old: T, new: T },
+}
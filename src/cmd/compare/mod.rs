@@ -0,0 +1,179 @@
+use std::{collections::BTreeMap, error::Error, fs, path::PathBuf};
+
+use clap::Args;
+
+use crate::cmd::list::{self, data::*};
+use crate::{binary, cli::GlobalOptions, output::PrintOutput};
+
+use self::data::*;
+
+pub mod data;
+
+// Command line arguments for the compare module.
+#[derive(Args)]
+pub struct Arguments {
+    /// The original binary.
+    old: PathBuf,
+
+    /// The binary that is compared against the original one.
+    new: PathBuf,
+}
+
+pub fn run(
+    arguments: &Arguments,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<(), Box<dyn Error>> {
+    let old_data = fs::read(&arguments.old)?;
+    let new_data = fs::read(&arguments.new)?;
+
+    let old_slices = binary::slices(&old_data, global.arch.as_deref())?;
+    let new_slices = binary::slices(&new_data, global.arch.as_deref())?;
+
+    // An architecture that only exists in one of the files is compared against nothing.
+    for (name, old_slice, new_slice) in binary::pair_slices(old_slices, new_slices)? {
+        if let Some(name) = &name {
+            output.begin_object(name);
+        }
+
+        let old_exports = old_slice.map(list::exports).transpose()?;
+        let new_exports = new_slice.map(list::exports).transpose()?;
+        let export_differences = differences(
+            old_exports.unwrap_or_default(),
+            new_exports.unwrap_or_default(),
+            export_key,
+        );
+        for difference in export_differences {
+            output.print_export_difference(&difference);
+        }
+
+        let old_imports = old_slice.map(list::imports).transpose()?;
+        let new_imports = new_slice.map(list::imports).transpose()?;
+        let import_differences = differences(
+            without_location(old_imports.unwrap_or_default()),
+            without_location(new_imports.unwrap_or_default()),
+            import_key,
+        );
+        for difference in import_differences {
+            output.print_import_difference(&difference);
+        }
+    }
+
+    Ok(())
+}
+
+/// Exports are identified by their name, unnamed exports by their ordinal.
+fn export_key(export: &Export) -> String {
+    match (export.function.is_empty(), export.ordinal) {
+        (true, Some(ordinal)) => format!("#{}", ordinal),
+        _ => export.function.clone(),
+    }
+}
+
+/// Imports are identified by the library and the function they refer to.
+fn import_key(import: &Import) -> (String, String) {
+    let function = match (import.function.is_empty(), import.ordinal) {
+        (true, Some(ordinal)) => format!("#{}", ordinal),
+        _ => import.function.clone(),
+    };
+
+    (import.library.clone(), function)
+}
+
+/// Hints and import address table entries move with every build, they are not compared.
+fn without_location(imports: Vec<Import>) -> Vec<Import> {
+    imports
+        .into_iter()
+        .map(|import| Import {
+            hint: None,
+            iat_address: None,
+            ..import
+        })
+        .collect()
+}
+
+/// Computes the differences between two lists, matching the entries by the given key.
+///
+/// Several entries may share a key, e.g. versioned ELF symbols. Those are matched
+/// with an equal entry first, the rest of them in order. The differences are ordered by the key.
+pub fn differences<T: PartialEq, K: Ord>(
+    old: Vec<T>,
+    new: Vec<T>,
+    key: impl Fn(&T) -> K,
+) -> Vec<Difference<T>> {
+    let mut entries = BTreeMap::<K, (Vec<T>, Vec<T>)>::new();
+    for entry in old {
+        entries.entry(key(&entry)).or_default().0.push(entry);
+    }
+    for entry in new {
+        entries.entry(key(&entry)).or_default().1.push(entry);
+    }
+
+    let mut differences = Vec::<Difference<T>>::new();
+    for (mut old, new) in entries.into_values() {
+        let mut added = Vec::<T>::new();
+        for new_entry in new {
+            match old.iter().position(|old_entry| *old_entry == new_entry) {
+                Some(index) => {
+                    old.remove(index);
+                }
+                None => added.push(new_entry),
+            }
+        }
+
+        let mut old = old.into_iter();
+        for new_entry in added {
+            differences.push(match old.next() {
+                Some(old_entry) => Difference::Changed {
+                    old: old_entry,
+                    new: new_entry,
+                },
+                None => Difference::Added(new_entry),
+            });
+        }
+        differences.extend(old.map(Difference::Removed));
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(old: Vec<(&str, u32)>, new: Vec<(&str, u32)>) -> Vec<String> {
+        differences(old, new, |(name, _)| *name)
+            .into_iter()
+            .map(|difference| match difference {
+                Difference::Added((name, value)) => format!("+{}@{}", name, value),
+                Difference::Removed((name, value)) => format!("-{}@{}", name, value),
+                Difference::Changed { old, new } => {
+                    format!("{}@{}->{}@{}", old.0, old.1, new.0, new.1)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn entries_are_matched_by_key() {
+        assert_eq!(
+            changes(
+                vec![("b", 1), ("a", 1), ("c", 1)],
+                vec![("a", 2), ("b", 1), ("d", 1)]
+            ),
+            vec!["a@1->a@2", "-c@1", "+d@1"]
+        );
+    }
+
+    #[test]
+    fn entries_with_the_same_key_are_kept() {
+        assert_eq!(
+            changes(vec![("a", 1), ("a", 2)], vec![("a", 2), ("a", 3), ("a", 4)]),
+            vec!["a@1->a@3", "+a@4"]
+        );
+        assert_eq!(
+            changes(vec![("a", 1), ("a", 1)], vec![("a", 1)]),
+            vec!["-a@1"]
+        );
+    }
+}
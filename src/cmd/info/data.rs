@@ -1,7 +1,11 @@
 use bitflags::bitflags;
 use object::{
-    pe, Architecture, Endianness, ObjectKind, SectionKind, SymbolKind, SymbolScope, SymbolSection,
+    elf, pe, Architecture, Endianness, ObjectKind, SectionKind, SymbolKind, SymbolScope,
+    SymbolSection,
 };
+use serde::Serialize;
+
+use crate::output::json;
 
 // use bitflags! to make it easier to print the set file flags.
 bitflags! {
@@ -154,39 +158,248 @@ bitflags! {
     }
 }
 
+bitflags! {
+    #[derive(Debug)]
+    pub struct DllCharacteristics : u16 {
+        // Image can handle a high entropy 64-bit virtual address space.
+        const HIGH_ENTROPY_VA = pe::IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA;
+        // DLL can be relocated at load time.
+        const DYNAMIC_BASE = pe::IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE;
+        // Code Integrity checks are enforced.
+        const FORCE_INTEGRITY = pe::IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY;
+        // Image is NX compatible.
+        const NX_COMPAT = pe::IMAGE_DLLCHARACTERISTICS_NX_COMPAT;
+        // Isolation aware, but do not isolate the image.
+        const NO_ISOLATION = pe::IMAGE_DLLCHARACTERISTICS_NO_ISOLATION;
+        // Does not use structured exception (SE) handling.
+        // No SE handler may be called in this image.
+        const NO_SEH = pe::IMAGE_DLLCHARACTERISTICS_NO_SEH;
+        // Do not bind the image.
+        const NO_BIND = pe::IMAGE_DLLCHARACTERISTICS_NO_BIND;
+        // Image must execute in an AppContainer.
+        const APPCONTAINER = pe::IMAGE_DLLCHARACTERISTICS_APPCONTAINER;
+        // A WDM driver.
+        const WDM_DRIVER = pe::IMAGE_DLLCHARACTERISTICS_WDM_DRIVER;
+        // Image supports Control Flow Guard.
+        const GUARD_CF = pe::IMAGE_DLLCHARACTERISTICS_GUARD_CF;
+        // Terminal Server aware.
+        const TERMINAL_SERVER_AWARE = pe::IMAGE_DLLCHARACTERISTICS_TERMINAL_SERVER_AWARE;
+    }
+}
+
+bitflags! {
+    #[derive(Debug)]
+    pub struct ElfDynamicFlags : u32 {
+        // The object may reference the $ORIGIN substitution string.
+        const ORIGIN = elf::DF_ORIGIN;
+        // The symbol resolution starts with the object itself.
+        const SYMBOLIC = elf::DF_SYMBOLIC;
+        // Relocations may modify a non-writable segment.
+        const TEXTREL = elf::DF_TEXTREL;
+        // All relocations are processed before control is passed to the program.
+        const BIND_NOW = elf::DF_BIND_NOW;
+        // The object uses the static thread local storage model.
+        const STATIC_TLS = elf::DF_STATIC_TLS;
+    }
+}
+
+bitflags! {
+    #[derive(Debug)]
+    pub struct ElfDynamicFlags1 : u32 {
+        // Perform complete relocation processing.
+        const NOW = elf::DF_1_NOW;
+        // The symbols of the object are made available for later loaded objects.
+        const GLOBAL = elf::DF_1_GLOBAL;
+        // The object is a member of a group.
+        const GROUP = elf::DF_1_GROUP;
+        // The object cannot be unloaded.
+        const NODELETE = elf::DF_1_NODELETE;
+        // Filtees are loaded immediately instead of on demand.
+        const LOADFLTR = elf::DF_1_LOADFLTR;
+        // The object is initialized before any other object.
+        const INITFIRST = elf::DF_1_INITFIRST;
+        // The object cannot be added with dlopen.
+        const NOOPEN = elf::DF_1_NOOPEN;
+        // The object requires $ORIGIN processing.
+        const ORIGIN = elf::DF_1_ORIGIN;
+        // Direct bindings are enabled.
+        const DIRECT = elf::DF_1_DIRECT;
+        // Reserved.
+        const TRANS = elf::DF_1_TRANS;
+        // The object is an interposer.
+        const INTERPOSE = elf::DF_1_INTERPOSE;
+        // The default library search paths are ignored for this object.
+        const NODEFLIB = elf::DF_1_NODEFLIB;
+        // The object cannot be dumped with dldump.
+        const NODUMP = elf::DF_1_NODUMP;
+        // The object is a configuration alternative.
+        const CONFALT = elf::DF_1_CONFALT;
+        // The filtee terminates the filter search.
+        const ENDFILTEE = elf::DF_1_ENDFILTEE;
+        // Displacement relocations have been done.
+        const DISPRELDNE = elf::DF_1_DISPRELDNE;
+        // Displacement relocations are pending.
+        const DISPRELPND = elf::DF_1_DISPRELPND;
+        // The object has no direct bindings.
+        const NODIRECT = elf::DF_1_NODIRECT;
+        // Internal use.
+        const IGNMULDEF = elf::DF_1_IGNMULDEF;
+        // Internal use.
+        const NOKSYMS = elf::DF_1_NOKSYMS;
+        // Internal use.
+        const NOHDR = elf::DF_1_NOHDR;
+        // The object has been modified after it was built.
+        const EDITED = elf::DF_1_EDITED;
+        // Internal use.
+        const NORELOC = elf::DF_1_NORELOC;
+        // The object contains individual symbol interposers.
+        const SYMINTPOSE = elf::DF_1_SYMINTPOSE;
+        // Global auditing is required.
+        const GLOBAUDIT = elf::DF_1_GLOBAUDIT;
+        // The object defines singleton symbols.
+        const SINGLETON = elf::DF_1_SINGLETON;
+        // The object is a stub.
+        const STUB = elf::DF_1_STUB;
+        // The object is a position independent executable.
+        const PIE = elf::DF_1_PIE;
+    }
+}
+
+#[derive(Serialize)]
 pub struct Information {
+    #[serde(serialize_with = "json::debug")]
     pub architecture: Architecture,
+    #[serde(rename = "endianness", serialize_with = "json::debug")]
     pub endianess: Endianness,
     pub is_64: bool,
+    #[serde(serialize_with = "json::debug")]
     pub kind: ObjectKind,
     pub has_debug_symbols: bool,
     pub entry_address: u64,
+    #[serde(serialize_with = "json::flags")]
     pub coff_file_flags: Option<CoffFileFlags>,
     pub pdb_info: Option<PdbInfo>,
+    pub pe_info: Option<PeInfo>,
+    pub elf_info: Option<ElfInfo>,
+    pub macho_info: Option<MachOInfo>,
     pub sections: Vec<Section>,
     pub symbols: Vec<Symbol>,
 }
 
+#[derive(Serialize)]
+pub struct ElfInfo {
+    pub interpreter: Option<String>,
+    pub soname: Option<String>,
+    pub needed: Vec<String>,
+    pub rpath: Vec<String>,
+    pub runpath: Vec<String>,
+    #[serde(serialize_with = "json::flags")]
+    pub flags: Option<ElfDynamicFlags>,
+    #[serde(serialize_with = "json::flags")]
+    pub flags_1: Option<ElfDynamicFlags1>,
+    pub init: Option<u64>,
+    pub fini: Option<u64>,
+    pub init_array: Option<FunctionArray>,
+    pub fini_array: Option<FunctionArray>,
+}
+
+/// An array of function pointers, e.g. DT_INIT_ARRAY.
+#[derive(Serialize)]
+pub struct FunctionArray {
+    pub address: u64,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct PeInfo {
+    pub image_base: u64,
+    pub subsystem: String,
+    pub subsystem_version: Version,
+    pub os_version: Version,
+    pub image_version: Version,
+    pub linker_version: Version,
+    #[serde(serialize_with = "json::flags")]
+    pub dll_characteristics: Option<DllCharacteristics>,
+    pub section_alignment: u32,
+    pub file_alignment: u32,
+    pub size_of_image: u32,
+    pub size_of_headers: u32,
+    pub checksum: u32,
+    pub timestamp: u32,
+    pub size_of_stack_reserve: u64,
+    pub size_of_stack_commit: u64,
+    pub size_of_heap_reserve: u64,
+    pub size_of_heap_commit: u64,
+    pub data_directories: Vec<DataDirectory>,
+}
+
+#[derive(Serialize)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+}
+
+#[derive(Serialize)]
+pub struct DataDirectory {
+    pub name: String,
+    pub virtual_address: u32,
+    pub size: u32,
+}
+
+#[derive(Serialize)]
+pub struct MachOInfo {
+    pub uuid: Option<String>,
+    pub build_versions: Vec<MachOBuildVersion>,
+    pub id_dylib: Option<MachODylib>,
+    pub dylibs: Vec<MachODylib>,
+    pub rpaths: Vec<String>,
+    pub has_code_signature: bool,
+}
+
+#[derive(Serialize)]
+pub struct MachOBuildVersion {
+    pub platform: String,
+    pub minos: String,
+    pub sdk: String,
+}
+
+#[derive(Serialize)]
+pub struct MachODylib {
+    /// The load command that references the library, e.g. LC_LOAD_WEAK_DYLIB.
+    pub command: String,
+    pub name: String,
+    pub current_version: String,
+    pub compatibility_version: String,
+}
+
+#[derive(Serialize)]
 pub struct PdbInfo {
     pub age: u32,
     pub guid: String,
     pub path: String,
 }
 
+#[derive(Serialize)]
 pub struct Section {
     pub name: String,
+    #[serde(serialize_with = "json::debug")]
     pub kind: SectionKind,
     pub address: u64,
     pub size: u64,
     pub segment_name: Option<String>,
+    #[serde(serialize_with = "json::flags")]
     pub coff_section_flags: Option<CoffSectionFlags>,
 }
 
+#[derive(Serialize)]
 pub struct Symbol {
     pub name: String,
     pub address: u64,
     pub size: u64,
+    #[serde(serialize_with = "json::debug")]
     pub kind: SymbolKind,
+    #[serde(serialize_with = "json::debug")]
     pub scope: SymbolScope,
+    #[serde(serialize_with = "json::symbol_section")]
     pub section: SymbolSection,
 }
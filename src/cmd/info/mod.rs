@@ -1,10 +1,15 @@
-use std::{fs, path::PathBuf};
+use std::{error::Error, fs, path::PathBuf};
 
 use clap::Args;
-use object::{FileFlags, Object, ObjectSection, ObjectSymbol, SectionFlags};
+use object::{
+    macho as macho_format,
+    pe::{self, ImageNtHeaders32, ImageNtHeaders64},
+    read::pe::{ImageNtHeaders, ImageOptionalHeader, PeFile},
+    FileFlags, LittleEndian as LE, Object, ObjectSection, ObjectSymbol, SectionFlags,
+};
 use uuid::Uuid;
 
-use crate::output::PrintOutput;
+use crate::{binary, cli::GlobalOptions, elf, import_library, macho, output::PrintOutput};
 
 use self::data::*;
 
@@ -17,9 +22,34 @@ pub struct Arguments {
     file: PathBuf,
 }
 
-pub fn run(arguments: &Arguments, output: &dyn PrintOutput) {
-    let binary_data = fs::read(&arguments.file).unwrap();
-    let object_file = object::File::parse(&*binary_data).unwrap();
+pub fn run(
+    arguments: &Arguments,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<(), Box<dyn Error>> {
+    let binary_data = fs::read(&arguments.file)?;
+
+    for slice in binary::slices(&binary_data, global.arch.as_deref())? {
+        if let Some(name) = &slice.name {
+            output.begin_object(name);
+        }
+
+        if import_library::is_import_library(slice.data) {
+            return Err(format!(
+                "{} is an import library, not an image, `list exports` shows the exports it describes",
+                arguments.file.display()
+            )
+            .into());
+        }
+
+        print_information(slice.data, output)?;
+    }
+
+    Ok(())
+}
+
+fn print_information(binary_data: &[u8], output: &dyn PrintOutput) -> Result<(), Box<dyn Error>> {
+    let object_file = object::File::parse(binary_data)?;
 
     // File flags for PE/COFF
     let flags = if let FileFlags::Coff { characteristics: c } = object_file.flags() {
@@ -39,6 +69,52 @@ pub fn run(arguments: &Arguments, output: &dyn PrintOutput) {
         None
     };
 
+    // PE optional header
+    let pe_info = match object::FileKind::parse(binary_data)? {
+        object::FileKind::Pe32 => Some(pe_info::<ImageNtHeaders32>(binary_data)?),
+        object::FileKind::Pe64 => Some(pe_info::<ImageNtHeaders64>(binary_data)?),
+        _ => None,
+    };
+
+    // ELF dynamic linking infos
+    let elf_info = elf::dynamic(binary_data)?.map(|dynamic| ElfInfo {
+        interpreter: dynamic.interpreter,
+        soname: dynamic.soname,
+        needed: dynamic.needed,
+        rpath: dynamic.rpath,
+        runpath: dynamic.runpath,
+        flags: dynamic.flags.map(ElfDynamicFlags::from_bits_retain),
+        flags_1: dynamic.flags_1.map(ElfDynamicFlags1::from_bits_retain),
+        init: dynamic.init,
+        fini: dynamic.fini,
+        init_array: dynamic
+            .init_array
+            .map(|(address, size)| FunctionArray { address, size }),
+        fini_array: dynamic
+            .fini_array
+            .map(|(address, size)| FunctionArray { address, size }),
+    });
+
+    // Mach-O load commands
+    let macho_info = macho::load_commands(binary_data)?.map(|load_commands| MachOInfo {
+        uuid: load_commands
+            .uuid
+            .map(|uuid| Uuid::from_bytes(uuid).hyphenated().to_string()),
+        build_versions: load_commands
+            .build_versions
+            .iter()
+            .map(|build_version| MachOBuildVersion {
+                platform: platform_name(build_version.platform),
+                minos: macho::format_version(build_version.minos),
+                sdk: macho::format_version(build_version.sdk),
+            })
+            .collect(),
+        id_dylib: load_commands.id_dylib.as_ref().map(macho_dylib),
+        dylibs: load_commands.dylibs.iter().map(macho_dylib).collect(),
+        rpaths: load_commands.rpaths,
+        has_code_signature: load_commands.has_code_signature,
+    });
+
     // Sections
     let mut sections = Vec::<Section>::new();
     for section in object_file.sections() {
@@ -86,7 +162,146 @@ pub fn run(arguments: &Arguments, output: &dyn PrintOutput) {
         entry_address: object_file.entry(),
         coff_file_flags: flags,
         pdb_info: pdb,
+        pe_info,
+        elf_info,
+        macho_info,
         sections,
         symbols,
     });
+
+    Ok(())
+}
+
+fn pe_info<T: ImageNtHeaders>(binary_data: &[u8]) -> Result<PeInfo, Box<dyn Error>> {
+    let pe_file = PeFile::<T>::parse(binary_data)?;
+    let file_header = pe_file.nt_headers().file_header();
+    let optional_header = pe_file.nt_headers().optional_header();
+
+    let data_directories = pe_file
+        .data_directories()
+        .enumerate()
+        .map(|(index, directory)| DataDirectory {
+            name: DATA_DIRECTORY_NAMES
+                .get(index)
+                .unwrap_or(&"Unknown")
+                .to_string(),
+            virtual_address: directory.virtual_address.get(LE),
+            size: directory.size.get(LE),
+        })
+        .collect();
+
+    Ok(PeInfo {
+        image_base: optional_header.image_base(),
+        subsystem: subsystem_name(optional_header.subsystem()),
+        subsystem_version: Version {
+            major: optional_header.major_subsystem_version(),
+            minor: optional_header.minor_subsystem_version(),
+        },
+        os_version: Version {
+            major: optional_header.major_operating_system_version(),
+            minor: optional_header.minor_operating_system_version(),
+        },
+        image_version: Version {
+            major: optional_header.major_image_version(),
+            minor: optional_header.minor_image_version(),
+        },
+        linker_version: Version {
+            major: optional_header.major_linker_version().into(),
+            minor: optional_header.minor_linker_version().into(),
+        },
+        dll_characteristics: Some(DllCharacteristics::from_bits_retain(
+            optional_header.dll_characteristics(),
+        )),
+        section_alignment: optional_header.section_alignment(),
+        file_alignment: optional_header.file_alignment(),
+        size_of_image: optional_header.size_of_image(),
+        size_of_headers: optional_header.size_of_headers(),
+        checksum: optional_header.check_sum(),
+        timestamp: file_header.time_date_stamp.get(LE),
+        size_of_stack_reserve: optional_header.size_of_stack_reserve(),
+        size_of_stack_commit: optional_header.size_of_stack_commit(),
+        size_of_heap_reserve: optional_header.size_of_heap_reserve(),
+        size_of_heap_commit: optional_header.size_of_heap_commit(),
+        data_directories,
+    })
+}
+
+// The names of the data directories, in the order of the IMAGE_DIRECTORY_ENTRY_* indices.
+const DATA_DIRECTORY_NAMES: [&str; 16] = [
+    "Export",
+    "Import",
+    "Resource",
+    "Exception",
+    "Certificate",
+    "BaseRelocation",
+    "Debug",
+    "Architecture",
+    "GlobalPointer",
+    "TLS",
+    "LoadConfig",
+    "BoundImport",
+    "IAT",
+    "DelayImport",
+    "CLRRuntime",
+    "Reserved",
+];
+
+fn subsystem_name(subsystem: u16) -> String {
+    let name = match subsystem {
+        pe::IMAGE_SUBSYSTEM_UNKNOWN => "UNKNOWN",
+        pe::IMAGE_SUBSYSTEM_NATIVE => "NATIVE",
+        pe::IMAGE_SUBSYSTEM_WINDOWS_GUI => "WINDOWS_GUI",
+        pe::IMAGE_SUBSYSTEM_WINDOWS_CUI => "WINDOWS_CUI",
+        pe::IMAGE_SUBSYSTEM_OS2_CUI => "OS2_CUI",
+        pe::IMAGE_SUBSYSTEM_POSIX_CUI => "POSIX_CUI",
+        pe::IMAGE_SUBSYSTEM_NATIVE_WINDOWS => "NATIVE_WINDOWS",
+        pe::IMAGE_SUBSYSTEM_WINDOWS_CE_GUI => "WINDOWS_CE_GUI",
+        pe::IMAGE_SUBSYSTEM_EFI_APPLICATION => "EFI_APPLICATION",
+        pe::IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER => "EFI_BOOT_SERVICE_DRIVER",
+        pe::IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER => "EFI_RUNTIME_DRIVER",
+        pe::IMAGE_SUBSYSTEM_EFI_ROM => "EFI_ROM",
+        pe::IMAGE_SUBSYSTEM_XBOX => "XBOX",
+        pe::IMAGE_SUBSYSTEM_WINDOWS_BOOT_APPLICATION => "WINDOWS_BOOT_APPLICATION",
+        pe::IMAGE_SUBSYSTEM_XBOX_CODE_CATALOG => "XBOX_CODE_CATALOG",
+        _ => return format!("{:#x}", subsystem),
+    };
+
+    name.to_owned()
+}
+
+fn macho_dylib(dylib: &macho::Dylib) -> MachODylib {
+    let command = match dylib.command {
+        macho_format::LC_ID_DYLIB => "LC_ID_DYLIB".to_owned(),
+        macho_format::LC_LOAD_DYLIB => "LC_LOAD_DYLIB".to_owned(),
+        macho_format::LC_LOAD_WEAK_DYLIB => "LC_LOAD_WEAK_DYLIB".to_owned(),
+        macho_format::LC_REEXPORT_DYLIB => "LC_REEXPORT_DYLIB".to_owned(),
+        macho_format::LC_LAZY_LOAD_DYLIB => "LC_LAZY_LOAD_DYLIB".to_owned(),
+        macho_format::LC_LOAD_UPWARD_DYLIB => "LC_LOAD_UPWARD_DYLIB".to_owned(),
+        command => format!("{:#x}", command),
+    };
+
+    MachODylib {
+        command,
+        name: dylib.name.clone(),
+        current_version: macho::format_version(dylib.current_version),
+        compatibility_version: macho::format_version(dylib.compatibility_version),
+    }
+}
+
+fn platform_name(platform: u32) -> String {
+    let name = match platform {
+        macho_format::PLATFORM_MACOS => "macOS",
+        macho_format::PLATFORM_IOS => "iOS",
+        macho_format::PLATFORM_TVOS => "tvOS",
+        macho_format::PLATFORM_WATCHOS => "watchOS",
+        macho_format::PLATFORM_BRIDGEOS => "bridgeOS",
+        macho_format::PLATFORM_MACCATALYST => "Mac Catalyst",
+        macho_format::PLATFORM_IOSSIMULATOR => "iOS Simulator",
+        macho_format::PLATFORM_TVOSSIMULATOR => "tvOS Simulator",
+        macho_format::PLATFORM_WATCHOSSIMULATOR => "watchOS Simulator",
+        macho_format::PLATFORM_DRIVERKIT => "DriverKit",
+        _ => return format!("{:#x}", platform),
+    };
+
+    name.to_owned()
 }
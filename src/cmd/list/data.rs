@@ -1,26 +1,125 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::resolve::SearchRule;
+
+#[derive(Clone, Serialize)]
 pub struct Dependency {
     pub library: String,
+    pub path: Option<PathBuf>,
+    pub status: ResolutionStatus,
+    /// The rule of the loader's search order that located the library.
+    pub search_rule: Option<SearchRule>,
+    /// The rpath entry that located the library, as it is written in the binary.
+    pub rpath: Option<String>,
+    /// The DLL that implements the library, if the library is a Windows API set.
+    pub api_set_host: Option<String>,
+    /// The library is only loaded on the first call into it.
+    pub delay_load: bool,
+}
+
+/// The libraries a binary depends on and which library imports from which.
+pub struct DependencyGraph {
+    pub root: String,
+    pub dependencies: Vec<Dependency>,
+    pub edges: Vec<DependencyEdge>,
 }
 
+#[derive(Serialize)]
+pub struct DependencyEdge {
+    pub importer: String,
+    pub library: String,
+    /// The number of symbols imported from the library, `None` if it cannot be told.
+    pub symbols: Option<usize>,
+    pub delay_load: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionStatus {
+    /// No attempt was made to locate the library.
+    Skipped,
+    /// The library was found on disk.
+    Found,
+    /// The library could not be found in any of the search paths.
+    NotFound,
+}
+
+#[derive(Clone, PartialEq, Serialize)]
 pub struct Export {
     pub address: Option<u64>,
+    pub ordinal: Option<u32>,
     pub function: String,
     pub function_demangled: String,
     pub target: Option<ExportTarget>,
+    /// Set for exports that are described by an import library instead of the DLL itself.
+    pub import_library: Option<ImportLibraryEntry>,
 }
 
+#[derive(Clone, PartialEq, Serialize)]
 pub struct ExportTarget {
     pub library: String,
     pub forward: ForwardType,
+    /// Where the chain of forwards ends, only set when forwarders are followed.
+    pub resolution: Option<ForwardResolution>,
+}
+
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ForwardResolution {
+    /// The chain ends at an export with an address.
+    Resolved { library: String, address: u64 },
+    /// The library or the export a forward refers to does not exist, or the chain is circular.
+    Broken {
+        library: String,
+        forward: ForwardType,
+    },
 }
 
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ForwardType {
     Name(String),
     Ordinal(u64),
 }
 
+#[derive(Clone, PartialEq, Serialize)]
+pub struct ImportLibraryEntry {
+    /// The DLL that exports the function.
+    pub library: String,
+    /// The public symbol name the linker resolves, e.g. `_Sleep@4`.
+    pub symbol: String,
+    pub name_type: NameType,
+}
+
+/// How the exported name is derived from the symbol name of an import library entry.
+#[derive(Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameType {
+    /// The function is exported by ordinal only.
+    Ordinal,
+    /// The exported name is the symbol name.
+    Name,
+    /// The exported name is the symbol name without the leading `?`, `@` or `_`.
+    NoPrefix,
+    /// The exported name is the symbol name without the prefix and everything after the first `@`.
+    Undecorate,
+    /// The exported name is stored separately.
+    ExportAs,
+}
+
+#[derive(Clone, PartialEq, Serialize)]
 pub struct Import {
     pub library: String,
     pub function: String,
     pub function_demangled: String,
+    /// Set for imports by ordinal, these have no function name.
+    pub ordinal: Option<u32>,
+    /// The index into the export name table of the library that is tried first.
+    pub hint: Option<u16>,
+    /// The relative virtual address of the import address table entry.
+    pub iat_address: Option<u32>,
+    /// The import is resolved on the first call, through the delay-load import table.
+    pub delay_load: bool,
 }
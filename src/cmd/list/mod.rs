@@ -1,22 +1,29 @@
 use std::{
+    collections::{HashMap, HashSet, VecDeque},
     error::Error,
-    fs,
+    fs, mem,
     path::{Path, PathBuf},
     str,
 };
 
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
 use object::{
     pe::{ImageNtHeaders32, ImageNtHeaders64},
     read::pe::{self, ImageNtHeaders},
-    Object,
+    LittleEndian as LE, Object, ObjectKind, ObjectSymbol, SymbolKind,
 };
 use symbolic::{
     common::Name,
     demangle::{Demangle, DemangleOptions},
 };
 
-use crate::output::PrintOutput;
+use crate::{
+    binary,
+    cli::GlobalOptions,
+    elf, import_library, macho,
+    output::PrintOutput,
+    resolve::{Resolver, SearchRule},
+};
 
 use self::data::*;
 
@@ -28,167 +35,918 @@ pub enum Commands {
     Dependencies {
         /// The file from which to list the dependencies.
         file: PathBuf,
+
+        /// Locate each dependency on disk.
+        #[arg(long)]
+        resolve: bool,
+
+        /// Also list the dependencies of the dependencies, implies --resolve.
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// Whether to list delay-loaded dependencies.
+        #[arg(long, value_enum, default_value_t = DelayLoad::Include)]
+        delay_load: DelayLoad,
     },
 
     /// List all the exports of the given binary.
     Exports {
         /// The file from which to list the exports.
         file: PathBuf,
+
+        /// Only list the exports with this name, e.g. to find the archive member defining it.
+        #[arg(long)]
+        symbol: Option<String>,
+
+        /// Follow forwarded exports through the libraries they forward to.
+        #[arg(long)]
+        follow_forwarders: bool,
     },
 
     /// List all the imports of the given binary.
     Imports {
         /// The file from which to list the imports.
         file: PathBuf,
+
+        /// Whether to list delay-loaded imports.
+        #[arg(long, value_enum, default_value_t = DelayLoad::Include)]
+        delay_load: DelayLoad,
+
+        /// Look up the names of imports by ordinal in the exports of the imported libraries,
+        /// which are searched in this directory first. Can be given multiple times.
+        #[arg(long, value_name = "DIRECTORY")]
+        resolve_ordinals: Vec<PathBuf>,
     },
 }
 
-pub fn run(command: &Commands, output: &dyn PrintOutput) {
+/// Selects entries by whether they are delay-loaded.
+#[derive(ValueEnum, Copy, Clone)]
+pub enum DelayLoad {
+    /// List all entries.
+    Include,
+
+    /// Skip the delay-loaded entries.
+    Exclude,
+
+    /// Only list the delay-loaded entries.
+    Only,
+}
+
+impl DelayLoad {
+    fn matches(self, delay_load: bool) -> bool {
+        match self {
+            DelayLoad::Include => true,
+            DelayLoad::Exclude => !delay_load,
+            DelayLoad::Only => delay_load,
+        }
+    }
+}
+
+pub fn run(
+    command: &Commands,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<(), Box<dyn Error>> {
     match command {
-        Commands::Dependencies { file } => command.list_dependencies(file, output),
-        Commands::Exports { file } => command.list_exports(file, output),
-        Commands::Imports { file } => command.list_imports(file, output),
+        Commands::Dependencies {
+            file,
+            resolve,
+            recursive,
+            delay_load,
+        } => command.list_dependencies(
+            file,
+            *resolve || *recursive,
+            *recursive,
+            *delay_load,
+            global,
+            output,
+        ),
+        Commands::Exports {
+            file,
+            symbol,
+            follow_forwarders,
+        } => command.list_exports(file, symbol.as_deref(), *follow_forwarders, global, output),
+        Commands::Imports {
+            file,
+            delay_load,
+            resolve_ordinals,
+        } => command.list_imports(file, *delay_load, resolve_ordinals, global, output),
     }
-    .unwrap()
 }
 
 impl Commands {
     fn list_dependencies(
         &self,
         file: &Path,
+        resolve: bool,
+        recursive: bool,
+        delay_load: DelayLoad,
+        global: &GlobalOptions,
         output: &dyn PrintOutput,
     ) -> Result<(), Box<dyn Error>> {
         let binary_data = fs::read(file)?;
-        let object_file = object::File::parse(&*binary_data)?;
 
-        let imports = object_file.imports()?;
-        let mut last_library = "";
-        for import in imports {
-            let library = str::from_utf8(import.library()).unwrap();
+        for slice in binary::slices(&binary_data, global.arch.as_deref())? {
+            if let Some(name) = &slice.name {
+                output.begin_object(name);
+            }
 
-            if library != last_library {
-                output.print_dependency(&Dependency {
-                    library: library.to_owned(),
-                });
+            // An import library is no image that could be loaded, it names the DLLs it links to.
+            if import_library::is_import_library(slice.data) {
+                output.print_dependency_graph(&import_library_graph(file, slice.data)?);
+                continue;
             }
 
-            last_library = library;
+            let resolver = Resolver::new(file, slice.data, global)?;
+            let graph =
+                dependency_graph(file, slice.data, resolve, recursive, delay_load, &resolver)?;
+            output.print_dependency_graph(&graph);
         }
 
         Ok(())
     }
 
-    fn list_exports(&self, file: &Path, output: &dyn PrintOutput) -> Result<(), Box<dyn Error>> {
+    fn list_exports(
+        &self,
+        file: &Path,
+        symbol: Option<&str>,
+        follow_forwarders: bool,
+        global: &GlobalOptions,
+        output: &dyn PrintOutput,
+    ) -> Result<(), Box<dyn Error>> {
         let binary_data = fs::read(file)?;
-        match object::FileKind::parse(&*binary_data)? {
-            object::FileKind::Pe32 => {
-                self.list_exports_pe::<ImageNtHeaders32>(&binary_data, output)
+
+        for slice in binary::slices(&binary_data, global.arch.as_deref())? {
+            let mut exports = exports(slice.data)?;
+            if let Some(symbol) = symbol {
+                exports.retain(|export| {
+                    export.function == symbol || export.function_demangled == symbol
+                });
+
+                // Only the objects that define the symbol are of interest.
+                if exports.is_empty() {
+                    continue;
+                }
+            }
+
+            if follow_forwarders {
+                resolve_forwards(file, slice.data, global, &mut exports)?;
             }
-            object::FileKind::Pe64 => {
-                self.list_exports_pe::<ImageNtHeaders64>(&binary_data, output)
+
+            if let Some(name) = &slice.name {
+                output.begin_object(name);
+            }
+
+            for export in exports {
+                output.print_export(&export);
             }
-            _ => self.list_exports_default(&binary_data, output),
         }
+
+        Ok(())
     }
 
-    fn list_exports_default(
+    fn list_imports(
         &self,
-        binary_data: &Vec<u8>,
+        file: &Path,
+        delay_load: DelayLoad,
+        resolve_ordinals: &[PathBuf],
+        global: &GlobalOptions,
         output: &dyn PrintOutput,
     ) -> Result<(), Box<dyn Error>> {
-        let object_file = object::File::parse(&**binary_data)?;
+        let binary_data = fs::read(file)?;
 
-        let exports = object_file.exports()?;
-        for export in exports {
-            let function_name = str::from_utf8(export.name()).unwrap();
+        for slice in binary::slices(&binary_data, global.arch.as_deref())? {
+            if let Some(name) = &slice.name {
+                output.begin_object(name);
+            }
 
-            let demangled_name = Name::from(function_name);
-            let demangled_name = demangled_name.try_demangle(DemangleOptions::complete());
+            let mut imports = imports(slice.data)?;
+            if !resolve_ordinals.is_empty() {
+                name_ordinal_imports(file, slice.data, global, &mut imports, resolve_ordinals)?;
+            }
 
-            output.print_export(&Export {
-                address: Some(export.address()),
-                function: function_name.to_owned(),
-                function_demangled: demangled_name.to_string(),
-                target: None,
-            });
+            for import in imports {
+                if !delay_load.matches(import.delay_load) {
+                    continue;
+                }
+
+                output.print_import(&import);
+            }
         }
 
         Ok(())
     }
+}
 
-    fn list_exports_pe<T: ImageNtHeaders>(
-        &self,
-        binary_data: &Vec<u8>,
-        output: &dyn PrintOutput,
-    ) -> Result<(), Box<dyn Error>> {
-        let object_file = object::read::pe::PeFile::<T>::parse(&**binary_data)?;
-
-        if let Some(export_table) = object_file.export_table()? {
-            for export in export_table.exports()? {
-                let function_name = str::from_utf8(export.name.unwrap_or_default()).unwrap();
-
-                let demangled_name = Name::from(function_name);
-                let demangled_name = demangled_name.try_demangle(DemangleOptions::complete());
-
-                match export.target {
-                    pe::ExportTarget::Address(address) => {
-                        output.print_export(&Export {
-                            address: Some(address.into()),
-                            function: function_name.to_owned(),
-                            function_demangled: demangled_name.to_string(),
-                            target: None,
-                        });
+/// Follows the forwarded exports to the export the chain of forwards ends at.
+///
+/// The forwarded libraries are searched like the dependencies of the binary.
+pub fn resolve_forwards(
+    file: &Path,
+    binary_data: &[u8],
+    global: &GlobalOptions,
+    forwarded_exports: &mut [Export],
+) -> Result<(), Box<dyn Error>> {
+    let resolver = Resolver::new(file, binary_data, global)?;
+    let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+
+    // The exports of every library that was looked at, None if it could not be found.
+    let mut libraries = HashMap::<String, Option<Vec<Export>>>::new();
+    for export in forwarded_exports.iter_mut() {
+        let Some(target) = &mut export.target else {
+            continue;
+        };
+
+        let mut importer = file_name.to_string();
+        let mut library = target.library.clone();
+        let mut forward = target.forward.clone();
+        let mut visited = HashSet::<(String, String)>::new();
+        let resolution = loop {
+            // Forwards name the library without its extension.
+            let file_name = if library.contains('.') {
+                library.clone()
+            } else {
+                format!("{}.dll", library)
+            };
+            let file_name = resolver
+                .api_set_host(&file_name, Some(&importer))
+                .unwrap_or(file_name);
+            let key = resolver.normalize(&file_name);
+
+            let forward_key = match &forward {
+                ForwardType::Name(name) => name.clone(),
+                ForwardType::Ordinal(ordinal) => format!("#{}", ordinal),
+            };
+            if !visited.insert((key.clone(), forward_key)) {
+                break ForwardResolution::Broken { library, forward };
+            }
+
+            if !libraries.contains_key(&key) {
+                let library_exports = match resolver.resolve(&file_name) {
+                    Some(path) => Some(exports(&resolver.load(&path)?)?),
+                    None => None,
+                };
+                libraries.insert(key.clone(), library_exports);
+            }
+
+            let forwarded = libraries[&key].as_ref().and_then(|library_exports| {
+                library_exports.iter().find(|export| match &forward {
+                    ForwardType::Name(name) => export.function == *name,
+                    ForwardType::Ordinal(ordinal) => export
+                        .ordinal
+                        .is_some_and(|export_ordinal| u64::from(export_ordinal) == *ordinal),
+                })
+            });
+
+            match forwarded {
+                None => break ForwardResolution::Broken { library, forward },
+                Some(Export {
+                    target: Some(next), ..
+                }) => {
+                    importer = file_name;
+                    library = next.library.clone();
+                    forward = next.forward.clone();
+                }
+                Some(forwarded) => {
+                    break ForwardResolution::Resolved {
+                        library: file_name,
+                        address: forwarded.address.unwrap_or_default(),
                     }
-                    pe::ExportTarget::ForwardByName(dll, name) => {
-                        output.print_export(&Export {
-                            address: None,
-                            function: function_name.to_owned(),
-                            function_demangled: demangled_name.to_string(),
-                            target: Some(ExportTarget {
-                                library: str::from_utf8(dll).unwrap_or_default().to_owned(),
-                                forward: ForwardType::Name(
-                                    str::from_utf8(name).unwrap_or_default().to_owned(),
-                                ),
-                            }),
-                        });
+                }
+            }
+        };
+
+        target.resolution = Some(resolution);
+    }
+
+    Ok(())
+}
+
+/// Fills in the function names of imports by ordinal from the exports of the imported library.
+///
+/// Imports from libraries that cannot be found, or that do not export the ordinal, keep no name.
+pub fn name_ordinal_imports(
+    file: &Path,
+    binary_data: &[u8],
+    global: &GlobalOptions,
+    imports: &mut [Import],
+    directories: &[PathBuf],
+) -> Result<(), Box<dyn Error>> {
+    let mut resolver = Resolver::new(file, binary_data, global)?;
+    let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+    resolver.prepend_search_paths(directories);
+
+    // The exported names of every library, by ordinal.
+    let mut libraries = HashMap::<String, HashMap<u32, Export>>::new();
+    for import in imports.iter_mut() {
+        let Some(ordinal) = import.ordinal else {
+            continue;
+        };
+
+        let key = resolver.normalize(&import.library);
+        if !libraries.contains_key(&key) {
+            let mut named_exports = HashMap::<u32, Export>::new();
+            let library = resolver
+                .api_set_host(&import.library, Some(&file_name))
+                .unwrap_or(import.library.clone());
+            if let Some(path) = resolver.resolve(&library) {
+                for export in exports(&resolver.load(&path)?)? {
+                    if let Some(export_ordinal) = export.ordinal {
+                        named_exports.insert(export_ordinal, export);
                     }
-                    pe::ExportTarget::ForwardByOrdinal(dll, ordinal) => {
-                        output.print_export(&Export {
-                            address: None,
-                            function: function_name.to_owned(),
-                            function_demangled: demangled_name.to_string(),
-                            target: Some(ExportTarget {
-                                library: str::from_utf8(dll).unwrap_or_default().to_owned(),
-                                forward: ForwardType::Ordinal(ordinal.into()),
-                            }),
-                        })
+                }
+            }
+            libraries.insert(key.clone(), named_exports);
+        }
+
+        if let Some(export) = libraries[&key].get(&ordinal) {
+            import.function = export.function.clone();
+            import.function_demangled = export.function_demangled.clone();
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the libraries that the given binary depends on.
+pub fn dependencies(binary_data: &[u8]) -> Result<Vec<Dependency>, Box<dyn Error>> {
+    let mut dependencies = Vec::<Dependency>::new();
+
+    // ELF imports do not name their library, the dynamic section lists them instead.
+    if let Some(dynamic) = elf::dynamic(binary_data)? {
+        for library in dynamic.needed {
+            dependencies.push(Dependency {
+                library,
+                path: None,
+                status: ResolutionStatus::Skipped,
+                search_rule: None,
+                rpath: None,
+                api_set_host: None,
+                delay_load: false,
+            });
+        }
+
+        return Ok(dependencies);
+    }
+
+    // Mach-O load commands name every library, also those no symbol is imported from.
+    if let Some(load_commands) = macho::load_commands(binary_data)? {
+        for dylib in load_commands.dylibs {
+            dependencies.push(Dependency {
+                library: dylib.name,
+                path: None,
+                status: ResolutionStatus::Skipped,
+                search_rule: None,
+                rpath: None,
+                api_set_host: None,
+                delay_load: false,
+            });
+        }
+
+        return Ok(dependencies);
+    }
+
+    // Imports are grouped by library, delay-loaded libraries follow the ones loaded at startup.
+    let mut last_library: Option<(String, bool)> = None;
+    for import in imports(binary_data)? {
+        // Undefined symbols of object files are not bound to a library.
+        if import.library.is_empty() {
+            continue;
+        }
+
+        let library = Some((import.library.clone(), import.delay_load));
+        if library != last_library {
+            dependencies.push(Dependency {
+                library: import.library,
+                path: None,
+                status: ResolutionStatus::Skipped,
+                search_rule: None,
+                rpath: None,
+                api_set_host: None,
+                delay_load: import.delay_load,
+            });
+        }
+
+        last_library = library;
+    }
+
+    Ok(dependencies)
+}
+
+/// Returns the index of the library each import is bound to, `None` if no library provides it.
+///
+/// Imports that name their library are bound to it. ELF imports of unversioned symbols
+/// do not, they are bound to the first library in load order that exports them.
+/// `libraries` holds the name and the exported symbols of each library the importer
+/// depends on, in load order, without exports for libraries that could not be found.
+pub fn import_providers(
+    resolver: &Resolver,
+    importer: &str,
+    imports: &[Import],
+    libraries: &[(String, Option<HashSet<String>>)],
+) -> Vec<Option<usize>> {
+    let names: Vec<String> = libraries
+        .iter()
+        .map(|(library, _)| resolver.loaded_name(library, importer))
+        .collect();
+
+    imports
+        .iter()
+        .map(|import| {
+            if import.library.is_empty() {
+                libraries.iter().position(|(_, exports)| {
+                    exports
+                        .as_ref()
+                        .is_some_and(|exports| exports.contains(&import.function))
+                })
+            } else {
+                let library = resolver.loaded_name(&import.library, importer);
+                names.iter().position(|name| *name == library)
+            }
+        })
+        .collect()
+}
+
+/// Builds the graph of the libraries the given binary depends on.
+///
+/// When `resolve` is set, each library is located on disk and, if `recursive` is set
+/// as well, the dependencies of every located library are added to the graph.
+/// Each library is only added once, at the first place it is required, later imports
+/// of the same library are satisfied by the already loaded one, as done by the loaders.
+/// API sets are resolved to their host when an API set schema is given.
+/// Dependencies that do not match the `delay_load` filter are left out.
+pub fn dependency_graph(
+    file: &Path,
+    binary_data: &[u8],
+    resolve: bool,
+    recursive: bool,
+    delay_load: DelayLoad,
+    resolver: &Resolver,
+) -> Result<DependencyGraph, Box<dyn Error>> {
+    let root = file
+        .file_name()
+        .unwrap_or(file.as_os_str())
+        .to_string_lossy()
+        .into_owned();
+    let mut graph = DependencyGraph {
+        root: root.clone(),
+        dependencies: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    // The index into the dependencies and the exported symbols of every library.
+    let mut seen = HashMap::<String, (usize, Option<HashSet<String>>)>::new();
+    let mut pending = VecDeque::from([(root, resolver.executable().clone(), binary_data.to_vec())]);
+    while let Some((importer, loader, binary_data)) = pending.pop_front() {
+        let importer_imports = imports(&binary_data)?;
+
+        // The libraries of the importer in load order, with whether they are delay-loaded.
+        let mut importer_dependencies = Vec::<(String, bool)>::new();
+        for mut dependency in dependencies(&binary_data)? {
+            if !delay_load.matches(dependency.delay_load) {
+                continue;
+            }
+
+            let key = resolver.normalize(&dependency.library);
+            let is_delay_loaded = dependency.delay_load;
+            importer_dependencies.push((key.clone(), is_delay_loaded));
+
+            if !seen.contains_key(&key) {
+                let mut exported = None;
+                if resolve {
+                    if let Some(location) =
+                        resolver.locate_dependency(&mut dependency, &importer, &loader)
+                    {
+                        let library_data = resolver.load(&location.path)?;
+                        exported = Some(export_names(&library_data)?);
+
+                        // The binary itself is loaded before any of its dependencies.
+                        if recursive && location.rule != SearchRule::Loaded {
+                            let library_loader =
+                                resolver.loader(&location.path, &library_data, Some(&loader))?;
+                            pending.push_back((
+                                dependency.library.clone(),
+                                library_loader,
+                                library_data,
+                            ));
+                        }
                     }
+                } else {
+                    // API sets are no files, the loader redirects them to their host.
+                    dependency.api_set_host =
+                        resolver.api_set_host(&dependency.library, Some(&importer));
                 }
+
+                seen.insert(key.clone(), (graph.dependencies.len(), exported));
+                graph.dependencies.push(dependency);
             }
         }
 
-        Ok(())
+        let libraries: Vec<(String, Option<HashSet<String>>)> = importer_dependencies
+            .iter()
+            .map(|(key, _)| {
+                let (index, exported) = &seen[key];
+                (graph.dependencies[*index].library.clone(), exported.clone())
+            })
+            .collect();
+        let providers = import_providers(resolver, &importer, &importer_imports, &libraries);
+
+        // Unversioned ELF imports are bound to the first library that exports them,
+        // they can only be counted when the exports of every library are known.
+        let is_countable = importer_imports
+            .iter()
+            .all(|import| !import.library.is_empty())
+            || libraries.iter().all(|(_, exported)| exported.is_some());
+        for (key, is_delay_loaded) in &importer_dependencies {
+            let symbols = is_countable.then(|| {
+                importer_imports
+                    .iter()
+                    .zip(&providers)
+                    .filter(|(import, provider)| {
+                        import.delay_load == *is_delay_loaded
+                            && if import.library.is_empty() {
+                                provider.is_some_and(|provider| {
+                                    importer_dependencies[provider].0 == *key
+                                })
+                            } else {
+                                resolver.normalize(&import.library) == *key
+                            }
+                    })
+                    .count()
+            });
+
+            graph.edges.push(DependencyEdge {
+                importer: importer.clone(),
+                library: graph.dependencies[seen[key].0].library.clone(),
+                symbols,
+                delay_load: *is_delay_loaded,
+            });
+        }
     }
 
-    fn list_imports(&self, file: &Path, output: &dyn PrintOutput) -> Result<(), Box<dyn Error>> {
-        let binary_data = fs::read(file)?;
-        let object_file = object::File::parse(&*binary_data)?;
+    Ok(graph)
+}
+
+/// Builds the graph of the DLLs the members of an import library describe, which are not located.
+fn import_library_graph(
+    file: &Path,
+    binary_data: &[u8],
+) -> Result<DependencyGraph, Box<dyn Error>> {
+    let root = file
+        .file_name()
+        .unwrap_or(file.as_os_str())
+        .to_string_lossy()
+        .into_owned();
+    let mut graph = DependencyGraph {
+        root: root.clone(),
+        dependencies: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    for member in import_library::members(binary_data)? {
+        match graph
+            .edges
+            .iter_mut()
+            .find(|edge| edge.library == member.dll)
+        {
+            Some(edge) => edge.symbols = edge.symbols.map(|symbols| symbols + 1),
+            None => {
+                graph.edges.push(DependencyEdge {
+                    importer: root.clone(),
+                    library: member.dll.clone(),
+                    symbols: Some(1),
+                    delay_load: false,
+                });
+                graph.dependencies.push(Dependency {
+                    library: member.dll,
+                    path: None,
+                    status: ResolutionStatus::Skipped,
+                    search_rule: None,
+                    rpath: None,
+                    api_set_host: None,
+                    delay_load: false,
+                });
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Collects the names of all the exports of the given binary.
+pub fn export_names(binary_data: &[u8]) -> Result<HashSet<String>, Box<dyn Error>> {
+    Ok(exports(binary_data)?
+        .into_iter()
+        .map(|export| export.function)
+        .collect())
+}
+
+/// Collects all the exports of the given binary.
+pub fn exports(binary_data: &[u8]) -> Result<Vec<Export>, Box<dyn Error>> {
+    match object::FileKind::parse(binary_data)? {
+        object::FileKind::Archive => exports_import_library(binary_data),
+        _ if is_relocatable(binary_data)? => exports_relocatable(binary_data),
+        object::FileKind::Pe32 => exports_pe::<ImageNtHeaders32>(binary_data),
+        object::FileKind::Pe64 => exports_pe::<ImageNtHeaders64>(binary_data),
+        object::FileKind::Elf32 | object::FileKind::Elf64 => exports_elf(binary_data),
+        _ => exports_default(binary_data),
+    }
+}
+
+fn exports_default(binary_data: &[u8]) -> Result<Vec<Export>, Box<dyn Error>> {
+    let object_file = object::File::parse(binary_data)?;
+
+    let mut exports = Vec::<Export>::new();
+    for export in object_file.exports()? {
+        let function_name = str::from_utf8(export.name()).unwrap();
 
-        let imports = object_file.imports()?;
-        for import in imports {
-            let library = str::from_utf8(import.library()).unwrap();
-            let function_name = str::from_utf8(import.name()).unwrap();
+        exports.push(Export {
+            address: Some(export.address()),
+            ordinal: None,
+            function: function_name.to_owned(),
+            function_demangled: demangle(function_name),
+            target: None,
+            import_library: None,
+        });
+    }
+
+    Ok(exports)
+}
+
+fn exports_elf(binary_data: &[u8]) -> Result<Vec<Export>, Box<dyn Error>> {
+    let object_file = object::File::parse(binary_data)?;
+
+    // Object::exports skips indirect functions (IFUNC) and thread local symbols,
+    // so look at all defined dynamic symbols instead.
+    Ok(exports_symbols(object_file.dynamic_symbols()))
+}
+
+/// Object files have no export table, every global definition is visible to the linker.
+fn exports_relocatable(binary_data: &[u8]) -> Result<Vec<Export>, Box<dyn Error>> {
+    let object_file = object::File::parse(binary_data)?;
+
+    Ok(exports_symbols(object_file.symbols()))
+}
 
-            let demangled_name = Name::from(function_name);
-            let demangled_name = demangled_name.try_demangle(DemangleOptions::complete());
+/// The global, named definitions among the symbols.
+fn exports_symbols<'data, S: ObjectSymbol<'data>>(symbols: impl Iterator<Item = S>) -> Vec<Export> {
+    let mut exports = Vec::<Export>::new();
+    for symbol in symbols {
+        if symbol.is_undefined()
+            || !symbol.is_global()
+            || matches!(symbol.kind(), SymbolKind::Section | SymbolKind::File)
+        {
+            continue;
+        }
+
+        let function_name = symbol.name().unwrap_or_default();
+        if function_name.is_empty() {
+            continue;
+        }
+
+        exports.push(Export {
+            address: Some(symbol.address()),
+            ordinal: None,
+            function: function_name.to_owned(),
+            function_demangled: demangle(function_name),
+            target: None,
+            import_library: None,
+        });
+    }
+
+    exports
+}
+
+/// The exports of a DLL, as described by the short import members of its import library.
+fn exports_import_library(binary_data: &[u8]) -> Result<Vec<Export>, Box<dyn Error>> {
+    let mut exports = Vec::<Export>::new();
+    for member in import_library::members(binary_data)? {
+        let name_type = match member.name_type {
+            object::pe::IMPORT_OBJECT_ORDINAL => NameType::Ordinal,
+            object::pe::IMPORT_OBJECT_NAME => NameType::Name,
+            object::pe::IMPORT_OBJECT_NAME_NO_PREFIX => NameType::NoPrefix,
+            object::pe::IMPORT_OBJECT_NAME_UNDECORATE => NameType::Undecorate,
+            _ => NameType::ExportAs,
+        };
+        let function_name = member.name.unwrap_or_default();
+
+        exports.push(Export {
+            address: None,
+            ordinal: (name_type == NameType::Ordinal).then_some(member.ordinal_or_hint.into()),
+            function_demangled: demangle(&function_name),
+            function: function_name,
+            target: None,
+            import_library: Some(ImportLibraryEntry {
+                library: member.dll,
+                symbol: member.symbol,
+                name_type,
+            }),
+        });
+    }
+
+    Ok(exports)
+}
 
-            output.print_import(&Import {
-                library: library.to_owned(),
+fn exports_pe<T: ImageNtHeaders>(binary_data: &[u8]) -> Result<Vec<Export>, Box<dyn Error>> {
+    let object_file = object::read::pe::PeFile::<T>::parse(binary_data)?;
+
+    let mut exports = Vec::<Export>::new();
+    if let Some(export_table) = object_file.export_table()? {
+        for export in export_table.exports()? {
+            let function_name = str::from_utf8(export.name.unwrap_or_default()).unwrap();
+
+            let (address, target) = match export.target {
+                pe::ExportTarget::Address(address) => (Some(address.into()), None),
+                pe::ExportTarget::ForwardByName(dll, name) => (
+                    None,
+                    Some(ExportTarget {
+                        library: str::from_utf8(dll).unwrap_or_default().to_owned(),
+                        forward: ForwardType::Name(
+                            str::from_utf8(name).unwrap_or_default().to_owned(),
+                        ),
+                        resolution: None,
+                    }),
+                ),
+                pe::ExportTarget::ForwardByOrdinal(dll, ordinal) => (
+                    None,
+                    Some(ExportTarget {
+                        library: str::from_utf8(dll).unwrap_or_default().to_owned(),
+                        forward: ForwardType::Ordinal(ordinal.into()),
+                        resolution: None,
+                    }),
+                ),
+            };
+
+            exports.push(Export {
+                address,
+                ordinal: Some(export.ordinal),
                 function: function_name.to_owned(),
-                function_demangled: demangled_name.to_string(),
+                function_demangled: demangle(function_name),
+                target,
+                import_library: None,
             });
         }
+    }
 
-        Ok(())
+    Ok(exports)
+}
+
+/// Collects all the imports of the given binary.
+///
+/// For object files these are the undefined symbols, which are not bound to a library.
+pub fn imports(binary_data: &[u8]) -> Result<Vec<Import>, Box<dyn Error>> {
+    // Import libraries only describe exports.
+    if import_library::is_import_library(binary_data) {
+        return Ok(Vec::new());
+    }
+    if is_relocatable(binary_data)? {
+        return imports_relocatable(binary_data);
+    }
+
+    match object::FileKind::parse(binary_data)? {
+        object::FileKind::Pe32 => imports_pe::<ImageNtHeaders32>(binary_data),
+        object::FileKind::Pe64 => imports_pe::<ImageNtHeaders64>(binary_data),
+        _ => imports_default(binary_data),
+    }
+}
+
+fn imports_default(binary_data: &[u8]) -> Result<Vec<Import>, Box<dyn Error>> {
+    let object_file = object::File::parse(binary_data)?;
+    let import_libraries = elf::import_libraries(binary_data)?;
+
+    let mut imports = Vec::<Import>::new();
+    for import in object_file.imports()? {
+        let library = str::from_utf8(import.library()).unwrap();
+        let function_name = str::from_utf8(import.name()).unwrap();
+
+        // ELF imports do not name their library, use the symbol versioning instead.
+        let library = match import_libraries.get(function_name) {
+            Some(versioned_library) if library.is_empty() => versioned_library,
+            _ => library,
+        };
+
+        imports.push(Import {
+            library: library.to_owned(),
+            function: function_name.to_owned(),
+            function_demangled: demangle(function_name),
+            ordinal: None,
+            hint: None,
+            iat_address: None,
+            delay_load: false,
+        });
+    }
+
+    Ok(imports)
+}
+
+/// Reads the import lookup tables of the regular and the delay-load imports.
+///
+/// Object::imports skips imports by ordinal and drops the hints, so the tables are read directly.
+fn imports_pe<T: ImageNtHeaders>(binary_data: &[u8]) -> Result<Vec<Import>, Box<dyn Error>> {
+    let object_file = pe::PeFile::<T>::parse(binary_data)?;
+    let thunk_size = mem::size_of::<T::ImageThunkData>() as u32;
+
+    let mut imports = Vec::<Import>::new();
+    if let Some(import_table) = object_file.import_table()? {
+        let mut descriptors = import_table.descriptors()?;
+        while let Some(descriptor) = descriptors.next()? {
+            let library = import_table.name(descriptor.name.get(LE))?;
+            let library = str::from_utf8(library).unwrap_or_default();
+
+            // Without a lookup table, the unbound import address table holds the same entries.
+            let mut iat_address = descriptor.first_thunk.get(LE);
+            let lookup_table = match descriptor.original_first_thunk.get(LE) {
+                0 => iat_address,
+                address => address,
+            };
+
+            let mut thunks = import_table.thunks(lookup_table)?;
+            while let Some(thunk) = thunks.next::<T>()? {
+                let import = import_table.import::<T>(thunk)?;
+                imports.push(pe_import(library, import, iat_address, false));
+                iat_address += thunk_size;
+            }
+        }
     }
+
+    if let Some(import_table) = object_file
+        .data_directories()
+        .delay_load_import_table(binary_data, &object_file.section_table())?
+    {
+        let mut descriptors = import_table.descriptors()?;
+        while let Some(descriptor) = descriptors.next()? {
+            let library = import_table.name(descriptor.dll_name_rva.get(LE))?;
+            let library = str::from_utf8(library).unwrap_or_default();
+
+            let mut iat_address = descriptor.import_address_table_rva.get(LE);
+            let mut thunks = import_table.thunks(descriptor.import_name_table_rva.get(LE))?;
+            while let Some(thunk) = thunks.next::<T>()? {
+                let import = import_table.import::<T>(thunk)?;
+                imports.push(pe_import(library, import, iat_address, true));
+                iat_address += thunk_size;
+            }
+        }
+    }
+
+    Ok(imports)
+}
+
+fn pe_import(library: &str, import: pe::Import, iat_address: u32, delay_load: bool) -> Import {
+    let (function_name, ordinal, hint) = match import {
+        pe::Import::Name(hint, name) => {
+            (str::from_utf8(name).unwrap_or_default(), None, Some(hint))
+        }
+        pe::Import::Ordinal(ordinal) => ("", Some(ordinal.into()), None),
+    };
+
+    Import {
+        library: library.to_owned(),
+        function: function_name.to_owned(),
+        function_demangled: demangle(function_name),
+        ordinal,
+        hint,
+        iat_address: Some(iat_address),
+        delay_load,
+    }
+}
+
+fn imports_relocatable(binary_data: &[u8]) -> Result<Vec<Import>, Box<dyn Error>> {
+    let object_file = object::File::parse(binary_data)?;
+
+    let mut imports = Vec::<Import>::new();
+    for symbol in object_file.symbols() {
+        if !symbol.is_undefined() || !symbol.is_global() {
+            continue;
+        }
+
+        let function_name = symbol.name().unwrap_or_default();
+        if function_name.is_empty() {
+            continue;
+        }
+
+        imports.push(Import {
+            library: String::new(),
+            function: function_name.to_owned(),
+            function_demangled: demangle(function_name),
+            ordinal: None,
+            hint: None,
+            iat_address: None,
+            delay_load: false,
+        });
+    }
+
+    Ok(imports)
+}
+
+/// Whether the binary is an object file, e.g. a member of a static library.
+fn is_relocatable(binary_data: &[u8]) -> Result<bool, Box<dyn Error>> {
+    Ok(object::File::parse(binary_data)?.kind() == ObjectKind::Relocatable)
+}
+
+/// Demangles the given symbol name, falling back to the name itself.
+pub fn demangle(function_name: &str) -> String {
+    let demangled_name = Name::from(function_name);
+    demangled_name
+        .try_demangle(DemangleOptions::complete())
+        .to_string()
 }
@@ -1,2 +1,9 @@
+pub mod compare;
 pub mod info;
 pub mod list;
+pub mod provides;
+pub mod rdeps;
+pub mod tree;
+pub mod unused_deps;
+pub mod verify;
+pub mod why;
@@ -0,0 +1,15 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::cmd::list::data::Export;
+
+/// A library that exports a matching symbol.
+#[derive(Serialize)]
+pub struct Provider {
+    pub file: PathBuf,
+    /// The architecture or archive member that exports the symbol.
+    pub object: Option<String>,
+    #[serde(flatten)]
+    pub export: Export,
+}
@@ -0,0 +1,137 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use clap::{Args, ValueEnum};
+use regex::Regex;
+
+use crate::cmd::{list, rdeps};
+use crate::{binary, cli::GlobalOptions, output::PrintOutput, resolve};
+
+use self::data::*;
+
+pub mod data;
+
+// Command line arguments for the provides module.
+#[derive(Args)]
+pub struct Arguments {
+    /// The symbol to look for, either its raw or its demangled name.
+    symbol: String,
+
+    /// The directories that are searched for libraries, including their subdirectories.
+    /// Defaults to the directories the loaders search, e.g. `/usr/lib` or the Windows
+    /// system directory of `--windows-config`.
+    directories: Vec<PathBuf>,
+
+    /// How the symbol is compared with the exported names.
+    #[arg(long = "match", value_enum, default_value_t = MatchKind::Exact)]
+    match_kind: MatchKind,
+}
+
+/// Selects how the symbol is compared with the exported names.
+#[derive(ValueEnum, Copy, Clone)]
+pub enum MatchKind {
+    /// The name has to be equal to the symbol.
+    Exact,
+
+    /// The symbol is a glob pattern, e.g. `SSL_*`, that has to match the whole name.
+    Glob,
+
+    /// The symbol is a regular expression that has to match a part of the name.
+    Regex,
+}
+
+/// The compiled symbol.
+enum Pattern {
+    Exact(String),
+    Glob(glob::Pattern),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn new(symbol: &str, match_kind: MatchKind) -> Result<Self, Box<dyn Error>> {
+        Ok(match match_kind {
+            MatchKind::Exact => Pattern::Exact(symbol.to_owned()),
+            MatchKind::Glob => Pattern::Glob(glob::Pattern::new(symbol)?),
+            MatchKind::Regex => Pattern::Regex(Regex::new(symbol)?),
+        })
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Pattern::Exact(symbol) => name == symbol,
+            Pattern::Glob(pattern) => pattern.matches(name),
+            Pattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Prints every export below the directories that matches the symbol and returns whether there is any.
+pub fn run(
+    arguments: &Arguments,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<bool, Box<dyn Error>> {
+    let pattern = Pattern::new(&arguments.symbol, arguments.match_kind)?;
+
+    let mut files = Vec::<PathBuf>::new();
+    if arguments.directories.is_empty() {
+        // The loaders do not search the subdirectories of their default directories either.
+        for directory in resolve::default_search_directories(global)? {
+            if let Err(error) = rdeps::collect_files(&directory, false, &mut files) {
+                eprintln!("warning: skipping {}: {}", directory.display(), error);
+            }
+        }
+    }
+    for directory in &arguments.directories {
+        rdeps::collect_files(directory, true, &mut files)?;
+    }
+
+    let mut found = false;
+    for file in files {
+        let binary_data = match fs::read(&file) {
+            Ok(binary_data) => binary_data,
+            Err(error) => {
+                eprintln!("warning: skipping {}: {}", file.display(), error);
+                continue;
+            }
+        };
+
+        // Anything that is not a binary, or not for the selected architecture, provides nothing.
+        let Ok(slices) = binary::slices(&binary_data, global.arch.as_deref()) else {
+            continue;
+        };
+
+        for slice in slices {
+            let Ok(exports) = list::exports(slice.data) else {
+                continue;
+            };
+
+            for export in exports {
+                // Import libraries also know the symbol the linker sees, which may be decorated.
+                let names = [
+                    Some(export.function.as_str()),
+                    Some(export.function_demangled.as_str()),
+                    export
+                        .import_library
+                        .as_ref()
+                        .map(|entry| entry.symbol.as_str()),
+                ];
+                if !names
+                    .into_iter()
+                    .flatten()
+                    .any(|name| pattern.matches(name))
+                {
+                    continue;
+                }
+
+                found = true;
+                output.print_provider(&Provider {
+                    file: file.clone(),
+                    object: slice.name.clone(),
+                    export,
+                });
+            }
+        }
+    }
+
+    Ok(found)
+}
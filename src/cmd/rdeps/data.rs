@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::cmd::list::data::Import;
+
+/// A binary that loads the library, directly or through other libraries.
+#[derive(Serialize)]
+pub struct ReverseDependency {
+    pub file: PathBuf,
+    /// The architecture of a universal binary.
+    pub architecture: Option<String>,
+    /// The shortest chain of libraries from the binary to the library.
+    pub chain: Vec<String>,
+    /// The binary itself imports the library.
+    pub direct: bool,
+    /// The symbols the binary imports from the library, only set if requested and direct.
+    pub symbols: Option<Vec<Import>>,
+}
@@ -0,0 +1,233 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use object::FileKind;
+
+use crate::cmd::list::{self, data::*, DelayLoad};
+use crate::cmd::why;
+use crate::resolve::Resolver;
+use crate::{binary, cli::GlobalOptions, output::PrintOutput};
+
+use self::data::*;
+
+pub mod data;
+
+// Command line arguments for the rdeps module.
+#[derive(Args)]
+pub struct Arguments {
+    /// The directory that is searched for binaries, including its subdirectories.
+    /// Libraries the loader does not find are also looked up in it.
+    directory: PathBuf,
+
+    /// The library whose consumers should be listed, either as it is imported or by its file name.
+    library: String,
+
+    /// Also lists the symbols each binary imports from the library.
+    #[arg(long)]
+    symbols: bool,
+}
+
+/// Prints every binary below the directory that loads the library and returns whether there is any.
+pub fn run(
+    arguments: &Arguments,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<bool, Box<dyn Error>> {
+    let mut files = Vec::<PathBuf>::new();
+    collect_files(&arguments.directory, true, &mut files)?;
+
+    // Libraries the loader does not find are looked up in the scanned directories.
+    let mut directories = Vec::<PathBuf>::new();
+    for directory in files.iter().filter_map(|file| file.parent()) {
+        if !directories.iter().any(|known| known == directory) {
+            directories.push(directory.to_path_buf());
+        }
+    }
+
+    let mut found = false;
+    for file in &files {
+        let binary_data = match fs::read(file) {
+            Ok(binary_data) => binary_data,
+            Err(error) => {
+                eprintln!("warning: skipping {}: {}", file.display(), error);
+                continue;
+            }
+        };
+
+        // Static libraries are linked into their consumers and never loaded.
+        if matches!(
+            FileKind::parse(&*binary_data),
+            Err(_) | Ok(FileKind::Archive)
+        ) {
+            continue;
+        }
+        let Ok(slices) = binary::slices(&binary_data, global.arch.as_deref()) else {
+            continue;
+        };
+
+        for slice in slices {
+            let dependency = match reverse_dependency(
+                arguments,
+                file,
+                slice.data,
+                slice.name.clone(),
+                &directories,
+                global,
+            ) {
+                Ok(dependency) => dependency,
+                Err(error) => {
+                    eprintln!("warning: skipping {}: {}", file.display(), error);
+                    continue;
+                }
+            };
+
+            if let Some(dependency) = dependency {
+                found = true;
+                output.print_reverse_dependency(&dependency);
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Adds the files in the directory, and below it if `recursive` is set, sorted by their path.
+///
+/// Symbolic links are skipped, they only point to files that are listed anyway.
+/// Subdirectories that cannot be read are reported and skipped.
+pub fn collect_files(
+    directory: &Path,
+    recursive: bool,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let mut entries = fs::read_dir(directory)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(error) => {
+                eprintln!("warning: skipping {}: {}", entry.path().display(), error);
+                continue;
+            }
+        };
+        if file_type.is_dir() {
+            if !recursive {
+                continue;
+            }
+            if let Err(error) = collect_files(&entry.path(), recursive, files) {
+                eprintln!("warning: skipping {}: {}", entry.path().display(), error);
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether the binary loads the library, by walking the libraries it loads.
+fn reverse_dependency(
+    arguments: &Arguments,
+    file: &Path,
+    binary_data: &[u8],
+    architecture: Option<String>,
+    directories: &[PathBuf],
+    global: &GlobalOptions,
+) -> Result<Option<ReverseDependency>, Box<dyn Error>> {
+    let mut resolver = Resolver::new(file, binary_data, global)?;
+    resolver.append_search_paths(directories);
+    let graph =
+        list::dependency_graph(file, binary_data, true, true, DelayLoad::Include, &resolver)?;
+
+    // The library does not consume itself.
+    let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+    if resolver.normalize(&file_name) == resolver.normalize(&arguments.library) {
+        return Ok(None);
+    }
+
+    let Some(chain) = why::shortest_chain(&graph, |dependency| {
+        why::is_library(dependency, &arguments.library, &resolver)
+    }) else {
+        return Ok(None);
+    };
+
+    let direct = chain.len() == 2;
+    let symbols = if arguments.symbols && direct {
+        Some(imported_symbols(
+            file,
+            binary_data,
+            &graph,
+            &chain[1],
+            &resolver,
+        )?)
+    } else {
+        None
+    };
+
+    Ok(Some(ReverseDependency {
+        file: file.to_path_buf(),
+        architecture,
+        chain,
+        direct,
+        symbols,
+    }))
+}
+
+/// Collects the imports of the binary that are bound to the library, each symbol once.
+///
+/// Imports that do not name their library, like unversioned ELF imports, are bound
+/// to the first library in load order that exports them.
+fn imported_symbols(
+    file: &Path,
+    binary_data: &[u8],
+    graph: &DependencyGraph,
+    library: &str,
+    resolver: &Resolver,
+) -> Result<Vec<Import>, Box<dyn Error>> {
+    let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+
+    // The libraries of the binary in load order, with their exports if they were found.
+    let mut libraries = Vec::<(String, Option<HashSet<String>>)>::new();
+    for edge in graph
+        .edges
+        .iter()
+        .filter(|edge| edge.importer == graph.root)
+    {
+        if libraries.iter().any(|(name, _)| *name == edge.library) {
+            continue;
+        }
+
+        let path = graph
+            .dependencies
+            .iter()
+            .find(|dependency| dependency.library == edge.library)
+            .and_then(|dependency| dependency.path.as_ref());
+        let exports = match path {
+            Some(path) => Some(list::export_names(&resolver.load(path)?)?),
+            None => None,
+        };
+        libraries.push((edge.library.clone(), exports));
+    }
+    let Some(index) = libraries.iter().position(|(name, _)| name == library) else {
+        return Ok(Vec::new());
+    };
+
+    let imports = list::imports(binary_data)?;
+    let providers = list::import_providers(resolver, &file_name, &imports, &libraries);
+
+    let mut listed = HashSet::<(String, Option<u32>)>::new();
+    let mut symbols = Vec::<Import>::new();
+    for (import, provider) in imports.into_iter().zip(providers) {
+        if provider == Some(index) && listed.insert((import.function.clone(), import.ordinal)) {
+            symbols.push(import);
+        }
+    }
+
+    Ok(symbols)
+}
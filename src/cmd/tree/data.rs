@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+use crate::cmd::list::data::Dependency;
+
+/// A library in the dependency tree, with the libraries it loads.
+#[derive(Serialize)]
+pub struct TreeNode {
+    #[serde(flatten)]
+    pub dependency: Dependency,
+    /// Set if the library is not expanded again, because it was already listed.
+    pub mark: Option<TreeMark>,
+    pub children: Vec<TreeNode>,
+}
+
+#[derive(Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TreeMark {
+    /// The library was already listed earlier in the tree.
+    Seen,
+    /// The library is one of its own importers.
+    Cycle,
+}
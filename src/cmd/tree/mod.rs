@@ -0,0 +1,153 @@
+use std::{collections::HashMap, error::Error, fs, path::Path, path::PathBuf};
+
+use clap::Args;
+
+use crate::cmd::list::{self, data::*, DelayLoad};
+use crate::resolve::Resolver;
+use crate::{binary, cli::GlobalOptions, output::PrintOutput};
+
+use self::data::*;
+
+pub mod data;
+
+// Command line arguments for the tree module.
+#[derive(Args)]
+pub struct Arguments {
+    /// The file whose dependencies should be displayed.
+    file: PathBuf,
+
+    /// Only descend this many levels below the file.
+    #[arg(long, value_name = "N")]
+    depth: Option<usize>,
+}
+
+pub fn run(
+    arguments: &Arguments,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<(), Box<dyn Error>> {
+    let binary_data = fs::read(&arguments.file)?;
+
+    for slice in binary::slices(&binary_data, global.arch.as_deref())? {
+        if let Some(name) = &slice.name {
+            output.begin_object(name);
+        }
+
+        let resolver = Resolver::new(&arguments.file, slice.data, global)?;
+        let tree = dependency_tree(&arguments.file, slice.data, arguments.depth, &resolver)?;
+        output.print_dependency_tree(&tree);
+    }
+
+    Ok(())
+}
+
+/// Builds the tree of the libraries the binary loads, from the dependency graph.
+///
+/// Like the loaders do, every library is only loaded once, in breadth-first order, and
+/// resolved for the importer that loads it first. It is only expanded there, other
+/// occurrences are marked as seen, or as a cycle if the library is one of its own importers.
+pub fn dependency_tree(
+    file: &Path,
+    binary_data: &[u8],
+    depth: Option<usize>,
+    resolver: &Resolver,
+) -> Result<TreeNode, Box<dyn Error>> {
+    let graph =
+        list::dependency_graph(file, binary_data, true, true, DelayLoad::Include, resolver)?;
+
+    // The edge each library was loaded through, which is the first one that names it.
+    let mut loaded_through = HashMap::<String, usize>::new();
+    for (index, edge) in graph.edges.iter().enumerate() {
+        loaded_through
+            .entry(resolver.normalize(&edge.library))
+            .or_insert(index);
+    }
+
+    let walk = TreeWalk {
+        graph: &graph,
+        resolver,
+        loaded_through,
+    };
+    let key = resolver.normalize(&graph.root);
+    let children = walk.children(&graph.root, &mut vec![key], depth);
+
+    Ok(TreeNode {
+        dependency: Dependency {
+            library: graph.root.clone(),
+            path: Some(file.to_path_buf()),
+            status: ResolutionStatus::Found,
+            search_rule: None,
+            rpath: None,
+            api_set_host: None,
+            delay_load: false,
+        },
+        mark: None,
+        children,
+    })
+}
+
+/// The dependency graph, walked depth-first to build the tree.
+struct TreeWalk<'a> {
+    graph: &'a DependencyGraph,
+    resolver: &'a Resolver,
+    /// The index of the edge each library is expanded at, by its key.
+    loaded_through: HashMap<String, usize>,
+}
+
+impl TreeWalk<'_> {
+    /// Builds the nodes of the libraries the importer loads.
+    ///
+    /// `chain` holds the keys of the libraries from the root down to the importer.
+    fn children(
+        &self,
+        importer: &str,
+        chain: &mut Vec<String>,
+        depth: Option<usize>,
+    ) -> Vec<TreeNode> {
+        let mut children = Vec::<TreeNode>::new();
+        if depth == Some(0) {
+            return children;
+        }
+
+        for (index, edge) in self.graph.edges.iter().enumerate() {
+            if edge.importer != importer {
+                continue;
+            }
+            let Some(dependency) = self
+                .graph
+                .dependencies
+                .iter()
+                .find(|dependency| dependency.library == edge.library)
+            else {
+                continue;
+            };
+
+            let key = self.resolver.normalize(&edge.library);
+            let mark = if chain.contains(&key) {
+                Some(TreeMark::Cycle)
+            } else if self.loaded_through.get(&key) != Some(&index) {
+                Some(TreeMark::Seen)
+            } else {
+                None
+            };
+
+            let mut node = TreeNode {
+                dependency: Dependency {
+                    delay_load: edge.delay_load,
+                    ..dependency.clone()
+                },
+                mark,
+                children: Vec::new(),
+            };
+            if mark.is_none() {
+                chain.push(key);
+                node.children = self.children(&edge.library, chain, depth.map(|depth| depth - 1));
+                chain.pop();
+            }
+
+            children.push(node);
+        }
+
+        children
+    }
+}
@@ -0,0 +1,78 @@
+use std::{collections::HashSet, error::Error, fs, path::Path, path::PathBuf};
+
+use clap::Args;
+
+use crate::cmd::list::{self, data::*};
+use crate::resolve::Resolver;
+use crate::{binary, cli::GlobalOptions, output::PrintOutput};
+
+// Command line arguments for the unused-deps module.
+#[derive(Args)]
+pub struct Arguments {
+    /// The file whose dependencies should be checked.
+    file: PathBuf,
+}
+
+/// Prints the dependencies no import is satisfied by and returns whether every dependency is used.
+pub fn run(
+    arguments: &Arguments,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<bool, Box<dyn Error>> {
+    let binary_data = fs::read(&arguments.file)?;
+
+    let mut all_used = true;
+    for slice in binary::slices(&binary_data, global.arch.as_deref())? {
+        if let Some(name) = &slice.name {
+            output.begin_object(name);
+        }
+
+        for dependency in unused_dependencies(&arguments.file, slice.data, global)? {
+            all_used = false;
+            output.print_dependency(&dependency);
+        }
+    }
+
+    Ok(all_used)
+}
+
+/// Collects the direct dependencies that provide none of the symbols the binary imports.
+///
+/// Imports that name their library are attributed to it, all others to the first
+/// dependency that exports them, like the loader binds them. Dependencies that
+/// cannot be found are never reported, there is no way to tell what they provide.
+pub fn unused_dependencies(
+    file: &Path,
+    binary_data: &[u8],
+    global: &GlobalOptions,
+) -> Result<Vec<Dependency>, Box<dyn Error>> {
+    let resolver = Resolver::new(file, binary_data, global)?;
+    let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+
+    let mut dependencies = list::dependencies(binary_data)?;
+    let mut libraries = Vec::<(String, Option<HashSet<String>>)>::new();
+    for dependency in &mut dependencies {
+        let exports =
+            match resolver.locate_dependency(dependency, &file_name, resolver.executable()) {
+                Some(location) => Some(list::export_names(&resolver.load(&location.path)?)?),
+                None => None,
+            };
+        libraries.push((dependency.library.clone(), exports));
+    }
+
+    let imports = list::imports(binary_data)?;
+    let mut used = vec![false; dependencies.len()];
+    for index in list::import_providers(&resolver, &file_name, &imports, &libraries)
+        .into_iter()
+        .flatten()
+    {
+        used[index] = true;
+    }
+
+    Ok(dependencies
+        .into_iter()
+        .zip(used)
+        .filter(|(dependency, used)| !used && dependency.status == ResolutionStatus::Found)
+        .map(|(dependency, _)| dependency)
+        .collect())
+}
@@ -0,0 +1,19 @@
+use serde::Serialize;
+
+use crate::cmd::list::data::*;
+
+#[derive(Serialize)]
+pub struct Verification {
+    pub library: String,
+    pub checked: usize,
+    pub unresolved: Vec<Import>,
+    /// Imports without a library that no library provides, while some libraries were not found.
+    pub unattributed: Vec<Import>,
+}
+
+impl Verification {
+    /// Whether imports from the library were found and all of them are resolved.
+    pub fn is_satisfied(&self) -> bool {
+        self.checked > 0 && self.unresolved.is_empty()
+    }
+}
@@ -0,0 +1,171 @@
+use std::{collections::HashSet, error::Error, fs, path::PathBuf};
+
+use clap::Args;
+
+use crate::cmd::list::{self, data::*};
+use crate::resolve::Resolver;
+use crate::{binary, cli::GlobalOptions, elf, import_library, output::PrintOutput};
+
+use self::data::*;
+
+pub mod data;
+
+// Command line arguments for the verify module.
+#[derive(Args)]
+pub struct Arguments {
+    /// The binary whose imports should be satisfied.
+    binary: PathBuf,
+
+    /// The library that should provide the imports.
+    library: PathBuf,
+}
+
+/// Runs the verification and returns whether all imports are satisfied.
+pub fn run(
+    arguments: &Arguments,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<bool, Box<dyn Error>> {
+    let binary_data = fs::read(&arguments.binary)?;
+    let library_data = fs::read(&arguments.library)?;
+
+    let binary_slices = binary::slices(&binary_data, global.arch.as_deref())?;
+    let library_slices = binary::slices(&library_data, global.arch.as_deref())?;
+
+    let mut satisfied = true;
+    for (name, binary_slice, library_slice) in binary::pair_slices(binary_slices, library_slices)? {
+        // Architectures the binary was not built for are of no concern.
+        let Some(binary_slice) = binary_slice else {
+            continue;
+        };
+        // The binary cannot be loaded with the library, the other architectures are still verified.
+        let Some(library_slice) = library_slice else {
+            eprintln!(
+                "error: {} does not contain architecture {}",
+                arguments.library.display(),
+                name.unwrap_or_default()
+            );
+            satisfied = false;
+            continue;
+        };
+
+        if let Some(name) = &name {
+            output.begin_object(name);
+        }
+
+        let resolver = Resolver::new(&arguments.binary, binary_slice, global)?;
+        let verification = verify(arguments, binary_slice, library_slice, &resolver)?;
+        output.print_verification(&verification);
+
+        satisfied &= verification.is_satisfied();
+    }
+
+    Ok(satisfied)
+}
+
+fn verify(
+    arguments: &Arguments,
+    binary_data: &[u8],
+    library_data: &[u8],
+    resolver: &Resolver,
+) -> Result<Verification, Box<dyn Error>> {
+    let exports = list::exports(library_data)?;
+
+    // The library is referenced either by its file name or by its SONAME.
+    // An import library stands in for the DLLs it describes.
+    let mut library_names = Vec::<String>::new();
+    if import_library::is_import_library(library_data) {
+        for export in &exports {
+            let Some(entry) = &export.import_library else {
+                continue;
+            };
+            if !library_names.contains(&entry.library) {
+                library_names.push(entry.library.clone());
+            }
+        }
+    } else if let Some(file_name) = arguments.library.file_name() {
+        library_names.push(file_name.to_string_lossy().into_owned());
+    }
+    if let Some(soname) = elf::dynamic(library_data)?.and_then(|dynamic| dynamic.soname) {
+        library_names.push(soname);
+    }
+
+    let binary_name = arguments
+        .binary
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let references_library = |import: &Import| {
+        let library = resolver.loaded_name(&import.library, &binary_name);
+        library_names
+            .iter()
+            .any(|name| resolver.normalize(name) == library)
+    };
+
+    let ordinals: HashSet<u32> = exports.iter().filter_map(|export| export.ordinal).collect();
+    let exports: HashSet<String> = exports.into_iter().map(|export| export.function).collect();
+
+    // ELF imports of unversioned symbols do not name their library. If the binary needs
+    // the library, they are bound to it unless a library loaded before it exports them.
+    let mut libraries = Vec::<(String, Option<HashSet<String>>)>::new();
+    let mut library_index = None;
+    for mut dependency in list::dependencies(binary_data)? {
+        let needed = resolver.normalize(&dependency.library);
+        let exported = if library_names
+            .iter()
+            .any(|name| resolver.normalize(name) == needed)
+        {
+            library_index = Some(libraries.len());
+            Some(exports.clone())
+        } else {
+            match resolver.locate_dependency(&mut dependency, &binary_name, resolver.executable()) {
+                Some(location) => Some(list::export_names(&resolver.load(&location.path)?)?),
+                None => None,
+            }
+        };
+        libraries.push((dependency.library, exported));
+    }
+
+    let imports = list::imports(binary_data)?;
+    let providers = list::import_providers(resolver, &binary_name, &imports, &libraries);
+    let weak_imports = elf::weak_imports(binary_data)?;
+    // An import nothing provides is the library's to provide only if no other library could.
+    let all_exports_known = libraries.iter().all(|(_, exports)| exports.is_some());
+
+    let mut checked = 0;
+    let mut unresolved = Vec::<Import>::new();
+    let mut unattributed = Vec::<Import>::new();
+    for (import, provider) in imports.into_iter().zip(providers) {
+        let is_unnamed = import.library.is_empty() && library_index.is_some();
+        if is_unnamed && provider.is_some() && provider != library_index {
+            continue;
+        }
+        if !is_unnamed && !references_library(&import) {
+            continue;
+        }
+        // Weak references that nothing provides stay unresolved on purpose.
+        if provider.is_none() && weak_imports.contains(&import.function) {
+            continue;
+        }
+        if is_unnamed && provider.is_none() && !all_exports_known {
+            unattributed.push(import);
+            continue;
+        }
+
+        checked += 1;
+        let is_resolved = match import.ordinal {
+            Some(ordinal) => ordinals.contains(&ordinal),
+            None => exports.contains(&import.function),
+        };
+        if !is_resolved {
+            unresolved.push(import);
+        }
+    }
+
+    Ok(Verification {
+        library: library_names.first().cloned().unwrap_or_default(),
+        checked,
+        unresolved,
+        unattributed,
+    })
+}
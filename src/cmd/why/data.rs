@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+/// The chains of libraries that pull a library into the process.
+#[derive(Serialize)]
+pub struct DependencyChains {
+    pub library: String,
+    /// Each chain starts at the binary and ends at the library.
+    pub chains: Vec<Vec<String>>,
+}
@@ -0,0 +1,317 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fs,
+    path::PathBuf,
+};
+
+use clap::Args;
+
+use crate::cmd::list::{self, data::*, DelayLoad};
+use crate::resolve::Resolver;
+use crate::{binary, cli::GlobalOptions, output::PrintOutput};
+
+use self::data::*;
+
+pub mod data;
+
+// Command line arguments for the why module.
+#[derive(Args)]
+pub struct Arguments {
+    /// The binary that loads the library.
+    file: PathBuf,
+
+    /// The library to explain, either as it is imported or by its file name.
+    library: String,
+}
+
+/// Prints the chains that pull in the library and returns whether there is any.
+pub fn run(
+    arguments: &Arguments,
+    global: &GlobalOptions,
+    output: &dyn PrintOutput,
+) -> Result<bool, Box<dyn Error>> {
+    let binary_data = fs::read(&arguments.file)?;
+
+    let mut found = false;
+    for slice in binary::slices(&binary_data, global.arch.as_deref())? {
+        if let Some(name) = &slice.name {
+            output.begin_object(name);
+        }
+
+        let resolver = Resolver::new(&arguments.file, slice.data, global)?;
+        let graph = list::dependency_graph(
+            &arguments.file,
+            slice.data,
+            true,
+            true,
+            DelayLoad::Include,
+            &resolver,
+        )?;
+
+        let chains = DependencyChains {
+            library: arguments.library.clone(),
+            chains: dependency_chains(&graph, |dependency| {
+                is_library(dependency, &arguments.library, &resolver)
+            }),
+        };
+
+        found |= !chains.chains.is_empty();
+        output.print_dependency_chains(&chains);
+    }
+
+    Ok(found)
+}
+
+/// Collects every chain of libraries from the binary to a library the predicate selects.
+///
+/// A library appears at most once in a chain, edges back to a library that is already
+/// part of the chain close a cycle and are left out. A chain ends at the selected library.
+pub fn dependency_chains(
+    graph: &DependencyGraph,
+    is_target: impl Fn(&Dependency) -> bool,
+) -> Vec<Vec<String>> {
+    let targets: HashSet<&str> = graph
+        .dependencies
+        .iter()
+        .filter(|dependency| is_target(dependency))
+        .map(|dependency| dependency.library.as_str())
+        .collect();
+
+    // A library imported regularly and delay-loaded has two edges, but is one step of a chain.
+    let mut children = HashMap::<&str, Vec<&str>>::new();
+    let mut importers = HashMap::<&str, Vec<&str>>::new();
+    for edge in &graph.edges {
+        let libraries = children.entry(&edge.importer).or_default();
+        if !libraries.contains(&edge.library.as_str()) {
+            libraries.push(&edge.library);
+            importers
+                .entry(&edge.library)
+                .or_default()
+                .push(&edge.importer);
+        }
+    }
+
+    // Only libraries from which a target can be reached are worth descending into.
+    let mut reaching: HashSet<&str> = targets.clone();
+    let mut pending: VecDeque<&str> = targets.iter().copied().collect();
+    while let Some(library) = pending.pop_front() {
+        for importer in importers.get(library).into_iter().flatten() {
+            if reaching.insert(importer) {
+                pending.push_back(importer);
+            }
+        }
+    }
+
+    let mut chains = Vec::<Vec<String>>::new();
+    let mut chain = vec![graph.root.as_str()];
+    collect_chains(&children, &targets, &reaching, &mut chain, &mut chains);
+    chains
+}
+
+/// Extends the chain by every child of its last library, depth first.
+fn collect_chains<'a>(
+    children: &HashMap<&'a str, Vec<&'a str>>,
+    targets: &HashSet<&str>,
+    reaching: &HashSet<&str>,
+    chain: &mut Vec<&'a str>,
+    chains: &mut Vec<Vec<String>>,
+) {
+    let library = chain[chain.len() - 1];
+    for &child in children.get(library).into_iter().flatten() {
+        if chain.contains(&child) || !reaching.contains(child) {
+            continue;
+        }
+
+        chain.push(child);
+        if targets.contains(child) {
+            chains.push(chain.iter().map(|library| library.to_string()).collect());
+        } else {
+            collect_chains(children, targets, reaching, chain, chains);
+        }
+        chain.pop();
+    }
+}
+
+/// Finds one of the shortest chains of libraries from the binary to a library the predicate selects.
+///
+/// Unlike `dependency_chains`, this walks the graph breadth first and stops at the first
+/// selected library, so it stays cheap for graphs with many chains.
+pub fn shortest_chain(
+    graph: &DependencyGraph,
+    is_target: impl Fn(&Dependency) -> bool,
+) -> Option<Vec<String>> {
+    let targets: HashSet<&str> = graph
+        .dependencies
+        .iter()
+        .filter(|dependency| is_target(dependency))
+        .map(|dependency| dependency.library.as_str())
+        .collect();
+
+    let mut children = HashMap::<&str, Vec<&str>>::new();
+    for edge in &graph.edges {
+        children
+            .entry(&edge.importer)
+            .or_default()
+            .push(&edge.library);
+    }
+
+    // The importer through which each library was reached first, the binary has none.
+    let root = graph.root.as_str();
+    let mut parents = HashMap::<&str, &str>::new();
+    let mut pending = VecDeque::from([root]);
+    while let Some(library) = pending.pop_front() {
+        for &child in children.get(library).into_iter().flatten() {
+            if child == root || parents.contains_key(child) {
+                continue;
+            }
+            parents.insert(child, library);
+
+            if targets.contains(child) {
+                let mut chain = vec![child.to_owned()];
+                let mut library = child;
+                while let Some(&parent) = parents.get(library) {
+                    chain.push(parent.to_owned());
+                    library = parent;
+                }
+                chain.reverse();
+                return Some(chain);
+            }
+            pending.push_back(child);
+        }
+    }
+
+    None
+}
+
+/// Whether the dependency is the library, by the name it is imported with, its API set host or its file name.
+pub fn is_library(dependency: &Dependency, library: &str, resolver: &Resolver) -> bool {
+    let file_name = dependency
+        .path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned());
+    let leaf_name = dependency.library.rsplit('/').next().map(str::to_owned);
+
+    [
+        Some(dependency.library.clone()),
+        dependency.api_set_host.clone(),
+        leaf_name,
+        file_name,
+    ]
+    .into_iter()
+    .flatten()
+    .any(|name| resolver.normalize(&name) == resolver.normalize(library))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency(library: &str) -> Dependency {
+        Dependency {
+            library: library.to_owned(),
+            path: None,
+            status: ResolutionStatus::Found,
+            search_rule: None,
+            rpath: None,
+            api_set_host: None,
+            delay_load: false,
+        }
+    }
+
+    fn edge(importer: &str, library: &str) -> DependencyEdge {
+        DependencyEdge {
+            importer: importer.to_owned(),
+            library: library.to_owned(),
+            symbols: None,
+            delay_load: false,
+        }
+    }
+
+    /// app loads a and b, which both load c, and c loads a again.
+    fn diamond_with_cycle() -> DependencyGraph {
+        DependencyGraph {
+            root: "app".to_owned(),
+            dependencies: ["a", "b", "c"].into_iter().map(dependency).collect(),
+            edges: vec![
+                edge("app", "a"),
+                edge("app", "b"),
+                edge("a", "c"),
+                edge("b", "c"),
+                edge("c", "a"),
+            ],
+        }
+    }
+
+    fn chains_to(graph: &DependencyGraph, library: &str) -> Vec<Vec<String>> {
+        dependency_chains(graph, |dependency| dependency.library == library)
+    }
+
+    #[test]
+    fn diamond_has_a_chain_through_each_branch() {
+        assert_eq!(
+            chains_to(&diamond_with_cycle(), "c"),
+            vec![vec!["app", "a", "c"], vec!["app", "b", "c"]]
+        );
+    }
+
+    #[test]
+    fn cycle_edges_are_left_out() {
+        assert_eq!(
+            chains_to(&diamond_with_cycle(), "a"),
+            vec![vec!["app", "a"], vec!["app", "b", "c", "a"]]
+        );
+    }
+
+    #[test]
+    fn library_importing_itself_has_no_second_step() {
+        let graph = DependencyGraph {
+            root: "app".to_owned(),
+            dependencies: vec![dependency("a")],
+            edges: vec![edge("app", "a"), edge("a", "a")],
+        };
+
+        assert_eq!(chains_to(&graph, "a"), vec![vec!["app", "a"]]);
+    }
+
+    #[test]
+    fn unreachable_library_has_no_chain() {
+        assert!(chains_to(&diamond_with_cycle(), "d").is_empty());
+    }
+
+    #[test]
+    fn shortest_chain_takes_the_first_branch() {
+        let chain = shortest_chain(&diamond_with_cycle(), |dependency| {
+            dependency.library == "c"
+        });
+
+        assert_eq!(
+            chain,
+            Some(vec!["app".to_owned(), "a".to_owned(), "c".to_owned()])
+        );
+    }
+
+    #[test]
+    fn shortest_chain_skips_longer_cycles() {
+        let chain = shortest_chain(&diamond_with_cycle(), |dependency| {
+            dependency.library == "a"
+        });
+
+        assert_eq!(chain, Some(vec!["app".to_owned(), "a".to_owned()]));
+    }
+
+    #[test]
+    fn shortest_chain_ignores_edges_back_to_the_binary() {
+        let graph = DependencyGraph {
+            root: "app".to_owned(),
+            dependencies: vec![dependency("a"), dependency("app")],
+            edges: vec![edge("app", "a"), edge("a", "app")],
+        };
+
+        assert_eq!(
+            shortest_chain(&graph, |dependency| dependency.library == "app"),
+            None
+        );
+    }
+}
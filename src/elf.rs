@@ -0,0 +1,198 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+
+use object::{
+    elf,
+    read::elf::{Dyn, FileHeader, ProgramHeader, Sym},
+    Endianness, FileKind,
+};
+
+/// The dynamic linking information of an ELF file.
+///
+/// Everything but the interpreter is decoded from the dynamic section.
+#[derive(Default)]
+pub struct Dynamic {
+    /// The program interpreter, read from the `PT_INTERP` program header.
+    pub interpreter: Option<String>,
+    pub soname: Option<String>,
+    pub needed: Vec<String>,
+    pub rpath: Vec<String>,
+    pub runpath: Vec<String>,
+    pub flags: Option<u32>,
+    pub flags_1: Option<u32>,
+    pub init: Option<u64>,
+    pub fini: Option<u64>,
+    /// The address and size in bytes of the initialization function pointers.
+    pub init_array: Option<(u64, u64)>,
+    /// The address and size in bytes of the termination function pointers.
+    pub fini_array: Option<(u64, u64)>,
+}
+
+/// Reads the dynamic section of the given binary.
+///
+/// Returns `None` if the binary is not an ELF file.
+pub fn dynamic(binary_data: &[u8]) -> Result<Option<Dynamic>, Box<dyn Error>> {
+    match FileKind::parse(binary_data)? {
+        FileKind::Elf32 => dynamic_elf::<elf::FileHeader32<Endianness>>(binary_data).map(Some),
+        FileKind::Elf64 => dynamic_elf::<elf::FileHeader64<Endianness>>(binary_data).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn dynamic_elf<Elf: FileHeader<Endian = Endianness>>(
+    binary_data: &[u8],
+) -> Result<Dynamic, Box<dyn Error>> {
+    let header = Elf::parse(binary_data)?;
+    let endian = header.endian()?;
+    let sections = header.sections(endian, binary_data)?;
+
+    let mut dynamic = Dynamic::default();
+
+    for segment in header.program_headers(endian, binary_data)? {
+        if segment.p_type(endian) == elf::PT_INTERP {
+            let value = segment.data(endian, binary_data).unwrap_or_default();
+            let value = value.strip_suffix(&[0]).unwrap_or(value);
+            dynamic.interpreter = Some(String::from_utf8_lossy(value).into_owned());
+        }
+    }
+
+    let Some((entries, link)) = sections.dynamic(endian, binary_data)? else {
+        return Ok(dynamic);
+    };
+    let strings = sections.strings(endian, binary_data, link)?;
+
+    let (mut init_array, mut init_array_size) = (None, 0);
+    let (mut fini_array, mut fini_array_size) = (None, 0);
+    for entry in entries {
+        let value: u64 = entry.d_val(endian).into();
+        match entry.tag32(endian) {
+            Some(elf::DT_SONAME) => {
+                let value = entry.string(endian, strings)?;
+                dynamic.soname = Some(String::from_utf8_lossy(value).into_owned());
+            }
+            Some(elf::DT_NEEDED) => {
+                let value = entry.string(endian, strings)?;
+                dynamic
+                    .needed
+                    .push(String::from_utf8_lossy(value).into_owned());
+            }
+            Some(elf::DT_RPATH) => {
+                let value = entry.string(endian, strings)?;
+                dynamic.rpath.extend(split_search_path(value));
+            }
+            Some(elf::DT_RUNPATH) => {
+                let value = entry.string(endian, strings)?;
+                dynamic.runpath.extend(split_search_path(value));
+            }
+            Some(elf::DT_FLAGS) => dynamic.flags = Some(value as u32),
+            Some(elf::DT_FLAGS_1) => dynamic.flags_1 = Some(value as u32),
+            Some(elf::DT_INIT) => dynamic.init = Some(value),
+            Some(elf::DT_FINI) => dynamic.fini = Some(value),
+            Some(elf::DT_INIT_ARRAY) => init_array = Some(value),
+            Some(elf::DT_INIT_ARRAYSZ) => init_array_size = value,
+            Some(elf::DT_FINI_ARRAY) => fini_array = Some(value),
+            Some(elf::DT_FINI_ARRAYSZ) => fini_array_size = value,
+            _ => {}
+        }
+    }
+    dynamic.init_array = init_array.map(|address| (address, init_array_size));
+    dynamic.fini_array = fini_array.map(|address| (address, fini_array_size));
+
+    Ok(dynamic)
+}
+
+/// Splits a colon separated list of directories, as used by DT_RPATH and DT_RUNPATH.
+fn split_search_path(value: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(value)
+        .split(':')
+        .filter(|directory| !directory.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Maps the undefined dynamic symbols of an ELF file to the library they are imported from.
+///
+/// ELF symbols are not bound to a library, only versioned symbols name the file that
+/// provides their version. Unversioned symbols are therefore missing from the map.
+pub fn import_libraries(binary_data: &[u8]) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    match FileKind::parse(binary_data)? {
+        FileKind::Elf32 => import_libraries_elf::<elf::FileHeader32<Endianness>>(binary_data),
+        FileKind::Elf64 => import_libraries_elf::<elf::FileHeader64<Endianness>>(binary_data),
+        _ => Ok(HashMap::new()),
+    }
+}
+
+fn import_libraries_elf<Elf: FileHeader<Endian = Endianness>>(
+    binary_data: &[u8],
+) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    let header = Elf::parse(binary_data)?;
+    let endian = header.endian()?;
+    let sections = header.sections(endian, binary_data)?;
+
+    let mut libraries = HashMap::<String, String>::new();
+    let Some((versyms, _)) = sections.gnu_versym(endian, binary_data)? else {
+        return Ok(libraries);
+    };
+
+    // Map each required version index to the file that provides it.
+    let mut files = HashMap::<u16, &[u8]>::new();
+    if let Some((mut verneeds, link)) = sections.gnu_verneed(endian, binary_data)? {
+        let strings = sections.strings(endian, binary_data, link)?;
+        while let Some((verneed, mut vernauxs)) = verneeds.next()? {
+            let file = verneed.file(endian, strings)?;
+            while let Some(vernaux) = vernauxs.next()? {
+                files.insert(vernaux.vna_other.get(endian) & elf::VERSYM_VERSION, file);
+            }
+        }
+    }
+
+    let symbols = sections.symbols(endian, binary_data, elf::SHT_DYNSYM)?;
+    for (index, symbol) in symbols.iter().enumerate() {
+        if !symbol.is_undefined(endian) {
+            continue;
+        }
+
+        let version = versyms
+            .get(index)
+            .map(|versym| versym.0.get(endian) & elf::VERSYM_VERSION);
+        if let Some(file) = version.and_then(|version| files.get(&version)) {
+            let name = symbol.name(endian, symbols.strings())?;
+            libraries.insert(
+                String::from_utf8_lossy(name).into_owned(),
+                String::from_utf8_lossy(file).into_owned(),
+            );
+        }
+    }
+
+    Ok(libraries)
+}
+
+/// Collects the undefined dynamic symbols that are weak, which may stay unresolved.
+pub fn weak_imports(binary_data: &[u8]) -> Result<HashSet<String>, Box<dyn Error>> {
+    match FileKind::parse(binary_data)? {
+        FileKind::Elf32 => weak_imports_elf::<elf::FileHeader32<Endianness>>(binary_data),
+        FileKind::Elf64 => weak_imports_elf::<elf::FileHeader64<Endianness>>(binary_data),
+        _ => Ok(HashSet::new()),
+    }
+}
+
+fn weak_imports_elf<Elf: FileHeader<Endian = Endianness>>(
+    binary_data: &[u8],
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let header = Elf::parse(binary_data)?;
+    let endian = header.endian()?;
+    let sections = header.sections(endian, binary_data)?;
+
+    let mut weak = HashSet::<String>::new();
+    let symbols = sections.symbols(endian, binary_data, elf::SHT_DYNSYM)?;
+    for symbol in symbols.iter() {
+        if symbol.is_undefined(endian) && symbol.st_bind() == elf::STB_WEAK {
+            let name = symbol.name(endian, symbols.strings())?;
+            weak.insert(String::from_utf8_lossy(name).into_owned());
+        }
+    }
+
+    Ok(weak)
+}
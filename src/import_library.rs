@@ -0,0 +1,72 @@
+use std::error::Error;
+
+use object::{
+    pe,
+    read::{
+        archive::ArchiveFile,
+        coff::{ImportFile, ImportName},
+    },
+    Architecture, FileKind, LittleEndian,
+};
+
+/// A short import member of an MSVC import library, describing one export of a DLL.
+pub struct ImportMember {
+    /// The name of the DLL that exports the symbol.
+    pub dll: String,
+    /// The public symbol name, as it is referenced by the linker.
+    pub symbol: String,
+    /// The name exported from the DLL, `None` for exports by ordinal.
+    pub name: Option<String>,
+    /// The ordinal for exports by ordinal, otherwise a hint into the export name table.
+    pub ordinal_or_hint: u16,
+    /// How the exported name is derived from the symbol, one of the `IMPORT_OBJECT_*` name types.
+    pub name_type: u16,
+    pub architecture: Architecture,
+}
+
+/// Whether the file is an import library, i.e. an archive with short import members.
+pub fn is_import_library(binary_data: &[u8]) -> bool {
+    members(binary_data).is_ok_and(|members| !members.is_empty())
+}
+
+/// Reads the short import members of the given archive.
+///
+/// Regular object members, e.g. the import descriptors, and broken members are skipped.
+pub fn members(binary_data: &[u8]) -> Result<Vec<ImportMember>, Box<dyn Error>> {
+    let mut members = Vec::<ImportMember>::new();
+    if FileKind::parse(binary_data)? != FileKind::Archive {
+        return Ok(members);
+    }
+
+    let archive = ArchiveFile::parse(binary_data)?;
+    for member in archive.members() {
+        // Members that cannot be parsed, e.g. empty ones, describe no export.
+        let data = member?.data(binary_data)?;
+        if !FileKind::parse(data).is_ok_and(|kind| kind == FileKind::CoffImport) {
+            continue;
+        }
+
+        // The name type and ordinal are only available from the raw header.
+        let (Ok(header), Ok(import_file)) = (
+            pe::ImportObjectHeader::parse(data, &mut 0),
+            ImportFile::parse(data),
+        ) else {
+            continue;
+        };
+        let name = match import_file.import() {
+            ImportName::Name(name) => Some(String::from_utf8_lossy(name).into_owned()),
+            ImportName::Ordinal(_) => None,
+        };
+
+        members.push(ImportMember {
+            dll: String::from_utf8_lossy(import_file.dll()).into_owned(),
+            symbol: String::from_utf8_lossy(import_file.symbol()).into_owned(),
+            name,
+            ordinal_or_hint: header.ordinal_or_hint.get(LittleEndian),
+            name_type: header.name_type(),
+            architecture: import_file.architecture(),
+        });
+    }
+
+    Ok(members)
+}
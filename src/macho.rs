@@ -0,0 +1,122 @@
+use std::error::Error;
+
+use object::{
+    macho,
+    read::macho::{LoadCommandVariant, MachHeader},
+    Endianness, FileKind,
+};
+
+/// The load commands of a Mach-O file, as far as they are relevant for linking.
+#[derive(Default)]
+pub struct LoadCommands {
+    pub uuid: Option<[u8; 16]>,
+    pub build_versions: Vec<BuildVersion>,
+    pub id_dylib: Option<Dylib>,
+    pub dylibs: Vec<Dylib>,
+    pub rpaths: Vec<String>,
+    pub has_code_signature: bool,
+}
+
+/// The platform and versions from LC_BUILD_VERSION or LC_VERSION_MIN_*.
+pub struct BuildVersion {
+    pub platform: u32,
+    pub minos: u32,
+    pub sdk: u32,
+}
+
+pub struct Dylib {
+    /// The load command, e.g. LC_LOAD_DYLIB or LC_REEXPORT_DYLIB.
+    pub command: u32,
+    pub name: String,
+    pub current_version: u32,
+    pub compatibility_version: u32,
+}
+
+/// Reads the load commands of the given binary.
+///
+/// Returns `None` if the binary is not a Mach-O file.
+pub fn load_commands(binary_data: &[u8]) -> Result<Option<LoadCommands>, Box<dyn Error>> {
+    match FileKind::parse(binary_data)? {
+        FileKind::MachO32 => {
+            load_commands_macho::<macho::MachHeader32<Endianness>>(binary_data).map(Some)
+        }
+        FileKind::MachO64 => {
+            load_commands_macho::<macho::MachHeader64<Endianness>>(binary_data).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn load_commands_macho<Mach: MachHeader<Endian = Endianness>>(
+    binary_data: &[u8],
+) -> Result<LoadCommands, Box<dyn Error>> {
+    let header = Mach::parse(binary_data, 0)?;
+    let endian = header.endian()?;
+
+    let mut load_commands = LoadCommands::default();
+    let mut commands = header.load_commands(endian, binary_data, 0)?;
+    while let Some(command) = commands.next()? {
+        match command.variant()? {
+            LoadCommandVariant::Uuid(uuid) => load_commands.uuid = Some(uuid.uuid),
+            LoadCommandVariant::BuildVersion(build_version) => {
+                load_commands.build_versions.push(BuildVersion {
+                    platform: build_version.platform.get(endian),
+                    minos: build_version.minos.get(endian),
+                    sdk: build_version.sdk.get(endian),
+                })
+            }
+            LoadCommandVariant::VersionMin(version_min) => {
+                let platform = match command.cmd() {
+                    macho::LC_VERSION_MIN_MACOSX => macho::PLATFORM_MACOS,
+                    macho::LC_VERSION_MIN_IPHONEOS => macho::PLATFORM_IOS,
+                    macho::LC_VERSION_MIN_TVOS => macho::PLATFORM_TVOS,
+                    macho::LC_VERSION_MIN_WATCHOS => macho::PLATFORM_WATCHOS,
+                    _ => 0,
+                };
+
+                load_commands.build_versions.push(BuildVersion {
+                    platform,
+                    minos: version_min.version.get(endian),
+                    sdk: version_min.sdk.get(endian),
+                })
+            }
+            LoadCommandVariant::IdDylib(dylib) | LoadCommandVariant::Dylib(dylib) => {
+                let name = command.string(endian, dylib.dylib.name)?;
+                let dylib = Dylib {
+                    command: command.cmd(),
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    current_version: dylib.dylib.current_version.get(endian),
+                    compatibility_version: dylib.dylib.compatibility_version.get(endian),
+                };
+
+                if dylib.command == macho::LC_ID_DYLIB {
+                    load_commands.id_dylib = Some(dylib);
+                } else {
+                    load_commands.dylibs.push(dylib);
+                }
+            }
+            LoadCommandVariant::Rpath(rpath) => {
+                let path = command.string(endian, rpath.path)?;
+                load_commands
+                    .rpaths
+                    .push(String::from_utf8_lossy(path).into_owned());
+            }
+            LoadCommandVariant::LinkeditData(_) if command.cmd() == macho::LC_CODE_SIGNATURE => {
+                load_commands.has_code_signature = true;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(load_commands)
+}
+
+/// Formats a version that is encoded in nibbles as xxxx.yy.zz.
+pub fn format_version(version: u32) -> String {
+    format!(
+        "{}.{}.{}",
+        version >> 16,
+        (version >> 8) & 0xff,
+        version & 0xff
+    )
+}
@@ -1,19 +1,52 @@
 use clap::Parser;
 use cli::{Cli, Commands};
-use std::error::Error;
+use std::process;
 
+mod binary;
 mod cli;
 mod cmd;
+mod elf;
+mod import_library;
+mod macho;
 mod output;
+mod resolve;
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() {
     let args = Cli::parse();
     let output = args.output.to_output();
 
-    match &args.command {
-        Commands::Info(arguments) => cmd::info::run(arguments, output.as_ref()),
-        Commands::List(command) => cmd::list::run(command, output.as_ref()),
-    }
+    let result = match &args.command {
+        Commands::Compare(arguments) => {
+            cmd::compare::run(arguments, &args.global, output.as_ref()).map(|()| true)
+        }
+        Commands::Info(arguments) => {
+            cmd::info::run(arguments, &args.global, output.as_ref()).map(|()| true)
+        }
+        Commands::List(command) => {
+            cmd::list::run(command, &args.global, output.as_ref()).map(|()| true)
+        }
+        Commands::Provides(arguments) => {
+            cmd::provides::run(arguments, &args.global, output.as_ref())
+        }
+        Commands::Rdeps(arguments) => cmd::rdeps::run(arguments, &args.global, output.as_ref()),
+        Commands::Tree(arguments) => {
+            cmd::tree::run(arguments, &args.global, output.as_ref()).map(|()| true)
+        }
+        Commands::UnusedDeps(arguments) => {
+            cmd::unused_deps::run(arguments, &args.global, output.as_ref())
+        }
+        Commands::Verify(arguments) => cmd::verify::run(arguments, &args.global, output.as_ref()),
+        Commands::Why(arguments) => cmd::why::run(arguments, &args.global, output.as_ref()),
+    };
+
+    output.finish();
 
-    Ok(())
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(2);
+        }
+    }
 }
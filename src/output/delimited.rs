@@ -0,0 +1,432 @@
+use std::cell::{Cell, RefCell};
+
+use crate::cmd::compare::data::*;
+use crate::cmd::info::data::*;
+use crate::cmd::list::data::*;
+use crate::cmd::provides::data::*;
+use crate::cmd::rdeps::data::*;
+use crate::cmd::tree::data::*;
+use crate::cmd::verify::data::*;
+use crate::cmd::why::data::*;
+use crate::resolve::SearchRule;
+
+use super::PrintOutput;
+
+const DEPENDENCY_HEADER: &[&str] = &[
+    "library",
+    "api_set_host",
+    "path",
+    "status",
+    "search_rule",
+    "rpath",
+    "delay_load",
+];
+const TREE_HEADER: &[&str] = &[
+    "depth",
+    "importer",
+    "library",
+    "api_set_host",
+    "path",
+    "status",
+    "search_rule",
+    "rpath",
+    "delay_load",
+    "mark",
+];
+const CHAIN_HEADER: &[&str] = &["chain", "depth", "library"];
+const PROVIDER_HEADER: &[&str] = &[
+    "file",
+    "object",
+    "address",
+    "ordinal",
+    "name",
+    "demangled",
+    "forward_library",
+    "forward_target",
+    "forward_status",
+    "final_library",
+    "final_address",
+    "import_library",
+    "import_symbol",
+    "name_type",
+];
+const REVERSE_DEPENDENCY_HEADER: &[&str] = &["file", "architecture", "direct", "chain", "symbols"];
+const EXPORT_HEADER: &[&str] = &[
+    "address",
+    "ordinal",
+    "name",
+    "demangled",
+    "forward_library",
+    "forward_target",
+    "forward_status",
+    "final_library",
+    "final_address",
+    "import_library",
+    "import_symbol",
+    "name_type",
+];
+const EXPORT_DIFFERENCE_HEADER: &[&str] = &[
+    "change",
+    "address",
+    "ordinal",
+    "name",
+    "demangled",
+    "forward_library",
+    "forward_target",
+    "forward_status",
+    "final_library",
+    "final_address",
+    "import_library",
+    "import_symbol",
+    "name_type",
+];
+const IMPORT_HEADER: &[&str] = &[
+    "library",
+    "name",
+    "demangled",
+    "ordinal",
+    "hint",
+    "iat_address",
+    "delay_load",
+];
+const IMPORT_DIFFERENCE_HEADER: &[&str] = &[
+    "change",
+    "library",
+    "name",
+    "demangled",
+    "ordinal",
+    "hint",
+    "iat_address",
+    "delay_load",
+];
+const INFORMATION_HEADER: &[&str] = &["property", "value"];
+
+/// Writes the entries as rows of delimiter separated values, e.g. CSV or TSV.
+///
+/// A header row is written before the first row and whenever the kind of row changes.
+/// Once an object was started, every row begins with the name of the object.
+pub(crate) struct Delimited {
+    separator: char,
+    header: Cell<Option<&'static [&'static str]>>,
+    object: RefCell<Option<String>>,
+}
+
+impl Delimited {
+    pub fn new(separator: char) -> Self {
+        Delimited {
+            separator,
+            header: Cell::new(None),
+            object: RefCell::new(None),
+        }
+    }
+
+    fn write_row(&self, header: &'static [&'static str], fields: &[String]) {
+        let object = self.object.borrow();
+
+        if self.header.get() != Some(header) {
+            if self.header.get().is_some() {
+                println!();
+            }
+
+            let mut names: Vec<String> = Vec::new();
+            if object.is_some() {
+                names.push("object".to_owned());
+            }
+            names.extend(header.iter().map(|name| name.to_string()));
+            println!("{}", self.join(&names));
+        }
+        self.header.set(Some(header));
+
+        match object.as_ref() {
+            Some(object) => {
+                let mut row = vec![object.clone()];
+                row.extend_from_slice(fields);
+                println!("{}", self.join(&row));
+            }
+            None => println!("{}", self.join(fields)),
+        }
+    }
+
+    /// Writes a row for the node and its children, which name their importer.
+    fn write_tree_rows(&self, node: &TreeNode, importer: &str, depth: usize) {
+        let mark = match node.mark {
+            Some(TreeMark::Seen) => "seen",
+            Some(TreeMark::Cycle) => "cycle",
+            None => "",
+        };
+
+        let mut fields = vec![depth.to_string(), importer.to_owned()];
+        fields.extend(dependency_fields(&node.dependency));
+        fields.push(mark.to_owned());
+        self.write_row(TREE_HEADER, &fields);
+
+        for child in &node.children {
+            self.write_tree_rows(child, &node.dependency.library, depth + 1);
+        }
+    }
+
+    fn join(&self, fields: &[String]) -> String {
+        fields
+            .iter()
+            .map(|field| self.quote(field))
+            .collect::<Vec<String>>()
+            .join(&self.separator.to_string())
+    }
+
+    /// Quotes the field if it contains the separator, quotes or line breaks.
+    fn quote(&self, field: &str) -> String {
+        if field.contains([self.separator, '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_owned()
+        }
+    }
+}
+
+impl PrintOutput for Delimited {
+    fn begin_object(&self, name: &str) {
+        *self.object.borrow_mut() = Some(name.to_owned());
+    }
+
+    fn print_dependency(&self, dependency: &Dependency) {
+        self.write_row(DEPENDENCY_HEADER, &dependency_fields(dependency));
+    }
+
+    fn print_dependency_graph(&self, graph: &DependencyGraph) {
+        for dependency in &graph.dependencies {
+            self.print_dependency(dependency);
+        }
+    }
+
+    fn print_dependency_tree(&self, tree: &TreeNode) {
+        for child in &tree.children {
+            self.write_tree_rows(child, &tree.dependency.library, 1);
+        }
+    }
+
+    fn print_dependency_chains(&self, chains: &DependencyChains) {
+        for (index, chain) in chains.chains.iter().enumerate() {
+            for (depth, library) in chain.iter().enumerate() {
+                self.write_row(
+                    CHAIN_HEADER,
+                    &[index.to_string(), depth.to_string(), library.clone()],
+                );
+            }
+        }
+    }
+
+    fn print_provider(&self, provider: &Provider) {
+        let mut fields = vec![
+            provider.file.display().to_string(),
+            provider.object.clone().unwrap_or_default(),
+        ];
+        fields.extend(export_fields(&provider.export));
+        self.write_row(PROVIDER_HEADER, &fields);
+    }
+
+    fn print_reverse_dependency(&self, dependency: &ReverseDependency) {
+        // Imports by ordinal have no name, they are written as @ordinal.
+        let symbols = dependency
+            .symbols
+            .iter()
+            .flatten()
+            .map(|import| match import.ordinal {
+                Some(ordinal) if import.function.is_empty() => format!("@{}", ordinal),
+                _ => import.function.clone(),
+            })
+            .collect::<Vec<String>>();
+
+        self.write_row(
+            REVERSE_DEPENDENCY_HEADER,
+            &[
+                dependency.file.display().to_string(),
+                dependency.architecture.clone().unwrap_or_default(),
+                dependency.direct.to_string(),
+                dependency.chain.join(" -> "),
+                symbols.join(" "),
+            ],
+        );
+    }
+
+    fn print_export(&self, export: &Export) {
+        self.write_row(EXPORT_HEADER, &export_fields(export));
+    }
+
+    fn print_export_difference(&self, difference: &Difference<Export>) {
+        for (change, export) in difference_rows(difference) {
+            let mut fields = vec![change.to_owned()];
+            fields.extend(export_fields(export));
+            self.write_row(EXPORT_DIFFERENCE_HEADER, &fields);
+        }
+    }
+
+    fn print_import(&self, import: &Import) {
+        self.write_row(IMPORT_HEADER, &import_fields(import));
+    }
+
+    fn print_import_difference(&self, difference: &Difference<Import>) {
+        for (change, import) in difference_rows(difference) {
+            let mut fields = vec![change.to_owned()];
+            fields.extend(import_fields(import));
+            self.write_row(IMPORT_DIFFERENCE_HEADER, &fields);
+        }
+    }
+
+    fn print_information(&self, information: &Information) {
+        let properties = [
+            ("architecture", format!("{:?}", information.architecture)),
+            ("endianness", format!("{:?}", information.endianess)),
+            ("is_64", information.is_64.to_string()),
+            ("kind", format!("{:?}", information.kind)),
+            (
+                "has_debug_symbols",
+                information.has_debug_symbols.to_string(),
+            ),
+            ("entry_address", format!("{:#x}", information.entry_address)),
+        ];
+
+        for (property, value) in properties {
+            self.write_row(INFORMATION_HEADER, &[property.to_owned(), value]);
+        }
+    }
+
+    fn print_verification(&self, verification: &Verification) {
+        for import in &verification.unresolved {
+            self.write_row(IMPORT_HEADER, &import_fields(import));
+        }
+    }
+}
+
+fn dependency_fields(dependency: &Dependency) -> Vec<String> {
+    let status = match dependency.status {
+        ResolutionStatus::Skipped => "skipped",
+        ResolutionStatus::Found => "found",
+        ResolutionStatus::NotFound => "not_found",
+    };
+    let search_rule = match dependency.search_rule {
+        Some(SearchRule::Path) => "path",
+        Some(SearchRule::UserDirectory) => "user_directory",
+        Some(SearchRule::Loaded) => "loaded",
+        Some(SearchRule::KnownDll) => "known_dll",
+        Some(SearchRule::ApplicationDirectory) => "application_directory",
+        Some(SearchRule::SystemDirectory) => "system_directory",
+        Some(SearchRule::WindowsDirectory) => "windows_directory",
+        Some(SearchRule::CurrentDirectory) => "current_directory",
+        Some(SearchRule::EnvironmentPath) => "environment_path",
+        Some(SearchRule::Rpath) => "rpath",
+        Some(SearchRule::LdLibraryPath) => "ld_library_path",
+        Some(SearchRule::Runpath) => "runpath",
+        Some(SearchRule::LdSoCache) => "ld_so_cache",
+        Some(SearchRule::DefaultDirectory) => "default_directory",
+        Some(SearchRule::LoaderPath) => "loader_path",
+        Some(SearchRule::ExecutablePath) => "executable_path",
+        Some(SearchRule::DyldLibraryPath) => "dyld_library_path",
+        Some(SearchRule::DyldFallbackLibraryPath) => "dyld_fallback_library_path",
+        None => "",
+    };
+
+    vec![
+        dependency.library.clone(),
+        dependency.api_set_host.clone().unwrap_or_default(),
+        dependency
+            .path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        status.to_owned(),
+        search_rule.to_owned(),
+        dependency.rpath.clone().unwrap_or_default(),
+        dependency.delay_load.to_string(),
+    ]
+}
+
+fn export_fields(export: &Export) -> Vec<String> {
+    let (forward_library, forward_target) = match &export.target {
+        Some(target) => (
+            target.library.clone(),
+            match &target.forward {
+                ForwardType::Name(name) => name.clone(),
+                ForwardType::Ordinal(ordinal) => ordinal.to_string(),
+            },
+        ),
+        None => (String::new(), String::new()),
+    };
+    let (forward_status, final_library, final_address) = match export
+        .target
+        .as_ref()
+        .and_then(|target| target.resolution.as_ref())
+    {
+        Some(ForwardResolution::Resolved { library, address }) => (
+            "resolved".to_owned(),
+            library.clone(),
+            format!("{:#x}", address),
+        ),
+        Some(ForwardResolution::Broken { library, .. }) => {
+            ("broken".to_owned(), library.clone(), String::new())
+        }
+        None => (String::new(), String::new(), String::new()),
+    };
+    let (import_library, import_symbol, name_type) = match &export.import_library {
+        Some(entry) => (
+            entry.library.clone(),
+            entry.symbol.clone(),
+            match entry.name_type {
+                NameType::Ordinal => "ordinal",
+                NameType::Name => "name",
+                NameType::NoPrefix => "no_prefix",
+                NameType::Undecorate => "undecorate",
+                NameType::ExportAs => "export_as",
+            }
+            .to_owned(),
+        ),
+        None => (String::new(), String::new(), String::new()),
+    };
+
+    vec![
+        export
+            .address
+            .map(|address| format!("{:#x}", address))
+            .unwrap_or_default(),
+        export
+            .ordinal
+            .map(|ordinal| ordinal.to_string())
+            .unwrap_or_default(),
+        export.function.clone(),
+        export.function_demangled.clone(),
+        forward_library,
+        forward_target,
+        forward_status,
+        final_library,
+        final_address,
+        import_library,
+        import_symbol,
+        name_type,
+    ]
+}
+
+fn import_fields(import: &Import) -> Vec<String> {
+    vec![
+        import.library.clone(),
+        import.function.clone(),
+        import.function_demangled.clone(),
+        import
+            .ordinal
+            .map(|ordinal| ordinal.to_string())
+            .unwrap_or_default(),
+        import.hint.map(|hint| hint.to_string()).unwrap_or_default(),
+        import
+            .iat_address
+            .map(|address| format!("{:#x}", address))
+            .unwrap_or_default(),
+        import.delay_load.to_string(),
+    ]
+}
+
+/// A changed entry is written as two rows, one with the old and one with the new values.
+fn difference_rows<T>(difference: &Difference<T>) -> Vec<(&'static str, &T)> {
+    match difference {
+        Difference::Added(entry) => vec![("added", entry)],
+        Difference::Removed(entry) => vec![("removed", entry)],
+        Difference::Changed { old, new } => vec![("changed_old", old), ("changed_new", new)],
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use crate::cmd::compare::data::*;
+use crate::cmd::info::data::*;
+use crate::cmd::list::data::*;
+use crate::cmd::provides::data::*;
+use crate::cmd::rdeps::data::*;
+use crate::cmd::tree::data::*;
+use crate::cmd::verify::data::*;
+use crate::cmd::why::data::*;
+
+use super::{plain::Plain, PrintOutput};
+
+/// Renders the dependency graph in the Graphviz DOT language.
+///
+/// Everything else is not a graph and is printed like the plain output.
+pub(crate) struct Dot {}
+
+impl PrintOutput for Dot {
+    fn begin_object(&self, name: &str) {
+        println!("// {}", name);
+    }
+
+    fn print_dependency(&self, dependency: &Dependency) {
+        Plain {}.print_dependency(dependency);
+    }
+
+    fn print_dependency_graph(&self, graph: &DependencyGraph) {
+        println!("digraph dependencies {{");
+        println!("    {} [shape=box];", quote(&graph.root));
+
+        for dependency in &graph.dependencies {
+            print_node(dependency);
+        }
+
+        // Delay-loaded libraries are drawn with dotted edges.
+        // Edges whose number of symbols is unknown are not labelled.
+        for edge in &graph.edges {
+            let mut attributes = Vec::<String>::new();
+            if let Some(symbols) = edge.symbols {
+                attributes.push(format!("label=\"{}\"", symbols));
+            }
+            if edge.delay_load {
+                attributes.push("style=dotted".to_owned());
+            }
+            let attributes = if attributes.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attributes.join(", "))
+            };
+            println!(
+                "    {} -> {}{};",
+                quote(&edge.importer),
+                quote(&edge.library),
+                attributes
+            );
+        }
+
+        println!("}}");
+    }
+
+    fn print_dependency_tree(&self, tree: &TreeNode) {
+        println!("digraph dependencies {{");
+        println!("    {} [shape=box];", quote(&tree.dependency.library));
+        print_tree_edges(tree);
+        println!("}}");
+    }
+
+    fn print_dependency_chains(&self, chains: &DependencyChains) {
+        println!("digraph dependencies {{");
+        println!("    {} [style=bold];", quote(&chains.library));
+
+        // Chains share their beginning, every edge is only drawn once.
+        let mut edges = HashSet::<(&str, &str)>::new();
+        for chain in &chains.chains {
+            for pair in chain.windows(2) {
+                if edges.insert((&pair[0], &pair[1])) {
+                    println!("    {} -> {};", quote(&pair[0]), quote(&pair[1]));
+                }
+            }
+        }
+
+        println!("}}");
+    }
+
+    fn print_provider(&self, provider: &Provider) {
+        Plain {}.print_provider(provider);
+    }
+
+    fn print_reverse_dependency(&self, dependency: &ReverseDependency) {
+        Plain {}.print_reverse_dependency(dependency);
+    }
+
+    fn print_export(&self, export: &Export) {
+        Plain {}.print_export(export);
+    }
+
+    fn print_export_difference(&self, difference: &Difference<Export>) {
+        Plain {}.print_export_difference(difference);
+    }
+
+    fn print_import(&self, import: &Import) {
+        Plain {}.print_import(import);
+    }
+
+    fn print_import_difference(&self, difference: &Difference<Import>) {
+        Plain {}.print_import_difference(difference);
+    }
+
+    fn print_information(&self, information: &Information) {
+        Plain {}.print_information(information);
+    }
+
+    fn print_verification(&self, verification: &Verification) {
+        Plain {}.print_verification(verification);
+    }
+}
+
+fn print_node(dependency: &Dependency) {
+    match (&dependency.status, &dependency.path) {
+        (ResolutionStatus::NotFound, _) => println!(
+            "    {} [style=dashed, color=red, fontcolor=red];",
+            quote(&dependency.library)
+        ),
+        (ResolutionStatus::Found, Some(path)) => println!(
+            "    {} [tooltip={}];",
+            quote(&dependency.library),
+            quote(&path.display().to_string())
+        ),
+        _ => println!("    {};", quote(&dependency.library)),
+    }
+
+    // API sets are drawn as notes, pointing to the DLL that implements them.
+    if let Some(host) = &dependency.api_set_host {
+        println!("    {} [shape=note];", quote(&dependency.library));
+        println!(
+            "    {} -> {} [style=dashed];",
+            quote(&dependency.library),
+            quote(host)
+        );
+    }
+}
+
+/// Draws the edges to the children of the node, and the children of those.
+///
+/// Edges that close a cycle are drawn red, delay-loaded libraries with dotted edges.
+fn print_tree_edges(node: &TreeNode) {
+    for child in &node.children {
+        if child.mark.is_none() {
+            print_node(&child.dependency);
+        }
+
+        let mut attributes = Vec::<&str>::new();
+        if child.mark == Some(TreeMark::Cycle) {
+            attributes.push("color=red");
+        }
+        if child.dependency.delay_load {
+            attributes.push("style=dotted");
+        }
+        let attributes = if attributes.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", attributes.join(", "))
+        };
+        println!(
+            "    {} -> {}{};",
+            quote(&node.dependency.library),
+            quote(&child.dependency.library),
+            attributes
+        );
+
+        print_tree_edges(child);
+    }
+}
+
+/// Quotes the identifier, escaping quotes and backslashes.
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
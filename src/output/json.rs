@@ -0,0 +1,175 @@
+use std::{cell::RefCell, fmt::Debug};
+
+use bitflags::Flags;
+use object::SymbolSection;
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::cmd::compare::data::*;
+use crate::cmd::info::data::*;
+use crate::cmd::list::data::*;
+use crate::cmd::provides::data::*;
+use crate::cmd::rdeps::data::*;
+use crate::cmd::tree::data::*;
+use crate::cmd::verify::data::*;
+use crate::cmd::why::data::*;
+
+use super::PrintOutput;
+
+/// The version of the document layout, increased on incompatible changes.
+const SCHEMA_VERSION: u64 = 1;
+
+/// Collects everything that is printed and writes a single JSON document when finished.
+pub(crate) struct Json {
+    document: RefCell<Map<String, Value>>,
+}
+
+impl Json {
+    pub fn new() -> Self {
+        let mut document = Map::new();
+        document.insert("schema_version".to_owned(), SCHEMA_VERSION.into());
+
+        Json {
+            document: RefCell::new(document),
+        }
+    }
+
+    /// Appends the value to the list with the given name.
+    fn push(&self, key: &str, value: &impl Serialize) {
+        let value = serde_json::to_value(value).unwrap();
+        let mut document = self.document.borrow_mut();
+
+        if let Value::Array(list) = current_object(&mut document)
+            .entry(key)
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            list.push(value);
+        }
+    }
+
+    /// Stores the value under the given name.
+    fn set(&self, key: &str, value: &impl Serialize) {
+        let value = serde_json::to_value(value).unwrap();
+        let mut document = self.document.borrow_mut();
+
+        current_object(&mut document).insert(key.to_owned(), value);
+    }
+}
+
+/// Returns the object values are currently added to.
+///
+/// This is the last entry of `objects` once an object was started, the document otherwise.
+fn current_object(document: &mut Map<String, Value>) -> &mut Map<String, Value> {
+    if !document.contains_key("objects") {
+        return document;
+    }
+
+    match document.get_mut("objects") {
+        Some(Value::Array(objects)) => match objects.last_mut() {
+            Some(Value::Object(object)) => object,
+            _ => unreachable!("objects are only added by begin_object"),
+        },
+        _ => unreachable!("objects are only added by begin_object"),
+    }
+}
+
+impl PrintOutput for Json {
+    fn begin_object(&self, name: &str) {
+        let mut object = Map::new();
+        object.insert("name".to_owned(), name.into());
+
+        let mut document = self.document.borrow_mut();
+        if let Value::Array(objects) = document
+            .entry("objects")
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            objects.push(Value::Object(object));
+        }
+    }
+
+    fn print_dependency(&self, dependency: &Dependency) {
+        self.push("dependencies", dependency);
+    }
+
+    fn print_dependency_graph(&self, graph: &DependencyGraph) {
+        for dependency in &graph.dependencies {
+            self.print_dependency(dependency);
+        }
+
+        for edge in &graph.edges {
+            self.push("dependency_edges", edge);
+        }
+    }
+
+    fn print_dependency_tree(&self, tree: &TreeNode) {
+        self.set("dependency_tree", tree);
+    }
+
+    fn print_dependency_chains(&self, chains: &DependencyChains) {
+        self.set("dependency_chains", chains);
+    }
+
+    fn print_provider(&self, provider: &Provider) {
+        self.push("providers", provider);
+    }
+
+    fn print_reverse_dependency(&self, dependency: &ReverseDependency) {
+        self.push("reverse_dependencies", dependency);
+    }
+
+    fn print_export(&self, export: &Export) {
+        self.push("exports", export);
+    }
+
+    fn print_export_difference(&self, difference: &Difference<Export>) {
+        self.push("export_differences", difference);
+    }
+
+    fn print_import(&self, import: &Import) {
+        self.push("imports", import);
+    }
+
+    fn print_import_difference(&self, difference: &Difference<Import>) {
+        self.push("import_differences", difference);
+    }
+
+    fn print_information(&self, information: &Information) {
+        self.set("information", information);
+    }
+
+    fn print_verification(&self, verification: &Verification) {
+        self.set("verification", verification);
+    }
+
+    fn finish(&self) {
+        let document = self.document.borrow();
+        println!("{}", serde_json::to_string_pretty(&*document).unwrap());
+    }
+}
+
+/// Serializes values of foreign types through their `Debug` representation.
+pub(crate) fn debug<T: Debug, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{:?}", value))
+}
+
+/// Serializes the set flags as a list of their names.
+pub(crate) fn flags<F: Flags, S: Serializer>(
+    flags: &Option<F>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match flags {
+        Some(flags) => serializer.collect_seq(flags.iter_names().map(|(name, _)| name)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serializes the section of a symbol as its index, or the kind of the special section.
+pub(crate) fn symbol_section<S: Serializer>(
+    section: &SymbolSection,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match section {
+        SymbolSection::Section(index) => serializer.serialize_u64(index.0 as u64),
+        _ => debug(section, serializer),
+    }
+}
@@ -1,27 +1,67 @@
 use clap::ValueEnum;
 
+use crate::cmd::compare::data::*;
 use crate::cmd::info::data::*;
 use crate::cmd::list::data::*;
+use crate::cmd::provides::data::*;
+use crate::cmd::rdeps::data::*;
+use crate::cmd::tree::data::*;
+use crate::cmd::verify::data::*;
+use crate::cmd::why::data::*;
 
+pub mod delimited;
+pub mod dot;
+pub mod json;
 pub mod plain;
 
 #[derive(ValueEnum, Copy, Clone)]
 pub enum OutputType {
     /// Outputs everything as plain text.
     Plain,
+
+    /// Outputs a single JSON document.
+    Json,
+
+    /// Outputs comma separated values with a header row.
+    Csv,
+
+    /// Outputs tab separated values with a header row.
+    Tsv,
+
+    /// Outputs the dependency graph in the Graphviz DOT language.
+    Dot,
 }
 
 impl OutputType {
     pub fn to_output(self) -> Box<dyn PrintOutput> {
         match self {
             OutputType::Plain => Box::new(plain::Plain {}),
+            OutputType::Json => Box::new(json::Json::new()),
+            OutputType::Csv => Box::new(delimited::Delimited::new(',')),
+            OutputType::Tsv => Box::new(delimited::Delimited::new('\t')),
+            OutputType::Dot => Box::new(dot::Dot {}),
         }
     }
 }
 
 pub trait PrintOutput {
+    /// Starts a new object, e.g. one architecture of a universal binary.
+    ///
+    /// Everything printed afterwards belongs to this object.
+    fn begin_object(&self, name: &str);
     fn print_dependency(&self, dependency: &Dependency);
+    fn print_dependency_graph(&self, graph: &DependencyGraph);
+    fn print_dependency_tree(&self, tree: &TreeNode);
+    fn print_dependency_chains(&self, chains: &DependencyChains);
+    fn print_provider(&self, provider: &Provider);
+    fn print_reverse_dependency(&self, dependency: &ReverseDependency);
     fn print_export(&self, export: &Export);
+    fn print_export_difference(&self, difference: &Difference<Export>);
     fn print_import(&self, import: &Import);
+    fn print_import_difference(&self, difference: &Difference<Import>);
     fn print_information(&self, information: &Information);
+    fn print_verification(&self, verification: &Verification);
+
+    /// Called once after the command finished printing.
+    fn finish(&self) {}
 }
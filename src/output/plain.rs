@@ -1,39 +1,89 @@
+use crate::cmd::compare::data::*;
 use crate::cmd::info::data::*;
 use crate::cmd::list::data::*;
+use crate::cmd::provides::data::*;
+use crate::cmd::rdeps::data::*;
+use crate::cmd::tree::data::*;
+use crate::cmd::verify::data::*;
+use crate::cmd::why::data::*;
+use crate::resolve::SearchRule;
 
 use super::PrintOutput;
 
 pub(crate) struct Plain {}
 
 impl PrintOutput for Plain {
+    fn begin_object(&self, name: &str) {
+        println!("[{}]", name);
+    }
+
     fn print_dependency(&self, dependency: &Dependency) {
-        println!("{}", dependency.library);
+        println!("{}", format_dependency(dependency));
     }
 
-    fn print_export(&self, export: &Export) {
-        print!(
-            "{:#018x}: {} {}",
-            export.address.unwrap_or_default(),
-            export.function,
-            export.function_demangled,
-        );
+    fn print_dependency_graph(&self, graph: &DependencyGraph) {
+        for dependency in &graph.dependencies {
+            self.print_dependency(dependency);
+        }
+    }
 
-        if let Some(target) = &export.target {
-            print!(" -> {}.", target.library);
-            match &target.forward {
-                ForwardType::Name(name) => print!("{}", name),
-                ForwardType::Ordinal(ordinal) => print!("{}", ordinal),
+    fn print_dependency_tree(&self, tree: &TreeNode) {
+        println!("{}", tree.dependency.library);
+        for child in &tree.children {
+            print_tree_node(child, 1);
+        }
+    }
+
+    fn print_dependency_chains(&self, chains: &DependencyChains) {
+        if chains.chains.is_empty() {
+            println!("{} is not loaded.", chains.library);
+        }
+
+        for chain in &chains.chains {
+            println!("{}", chain.join(" -> "));
+        }
+    }
+
+    fn print_provider(&self, provider: &Provider) {
+        match &provider.object {
+            Some(object) => print!("{} ({})", provider.file.display(), object),
+            None => print!("{}", provider.file.display()),
+        }
+        println!(": {}", format_export(&provider.export));
+    }
+
+    fn print_reverse_dependency(&self, dependency: &ReverseDependency) {
+        match &dependency.architecture {
+            Some(architecture) => print!("{} ({})", dependency.file.display(), architecture),
+            None => print!("{}", dependency.file.display()),
+        }
+        println!(": {}", dependency.chain.join(" -> "));
+
+        for import in dependency.symbols.iter().flatten() {
+            match import.ordinal {
+                Some(ordinal) if import.function.is_empty() => println!("    @{}", ordinal),
+                _ if import.function_demangled != import.function => {
+                    println!("    {} {}", import.function, import.function_demangled)
+                }
+                _ => println!("    {}", import.function),
             }
         }
+    }
 
-        println!();
+    fn print_export(&self, export: &Export) {
+        println!("{}", format_export(export));
+    }
+
+    fn print_export_difference(&self, difference: &Difference<Export>) {
+        print_difference(difference, format_export);
     }
 
     fn print_import(&self, import: &Import) {
-        println!(
-            "{}: {} {}",
-            import.library, import.function, import.function_demangled
-        );
+        println!("{}", format_import(import));
+    }
+
+    fn print_import_difference(&self, difference: &Difference<Import>) {
+        print_difference(difference, format_import);
     }
 
     fn print_information(&self, information: &Information) {
@@ -63,6 +113,122 @@ impl PrintOutput for Plain {
             println!("\tPath: {}", pdb.path);
         }
 
+        // PE optional header
+        if let Some(pe) = &information.pe_info {
+            println!();
+            println!("PE:");
+            println!("\tImage base: {:#x}", pe.image_base);
+            println!("\tSubsystem: {}", pe.subsystem);
+            println!(
+                "\tSubsystem version: {}.{}",
+                pe.subsystem_version.major, pe.subsystem_version.minor
+            );
+            println!(
+                "\tOS version: {}.{}",
+                pe.os_version.major, pe.os_version.minor
+            );
+            println!(
+                "\tImage version: {}.{}",
+                pe.image_version.major, pe.image_version.minor
+            );
+            println!(
+                "\tLinker version: {}.{}",
+                pe.linker_version.major, pe.linker_version.minor
+            );
+            if let Some(flags) = &pe.dll_characteristics {
+                println!("\tDllCharacteristics: {:?}", flags);
+            }
+            println!("\tSection alignment: {:#x}", pe.section_alignment);
+            println!("\tFile alignment: {:#x}", pe.file_alignment);
+            println!("\tSize of image: {:#x}", pe.size_of_image);
+            println!("\tSize of headers: {:#x}", pe.size_of_headers);
+            println!("\tChecksum: {:#x}", pe.checksum);
+            println!("\tTimestamp: {:#x}", pe.timestamp);
+            println!("\tStack reserve: {:#x}", pe.size_of_stack_reserve);
+            println!("\tStack commit: {:#x}", pe.size_of_stack_commit);
+            println!("\tHeap reserve: {:#x}", pe.size_of_heap_reserve);
+            println!("\tHeap commit: {:#x}", pe.size_of_heap_commit);
+
+            println!();
+            println!("Data directories:");
+            for directory in &pe.data_directories {
+                println!(
+                    "\t{}: {:#x} ({:#x} bytes)",
+                    directory.name, directory.virtual_address, directory.size
+                );
+            }
+        }
+
+        // ELF dynamic linking infos
+        if let Some(elf) = &information.elf_info {
+            println!();
+            println!("ELF:");
+            if let Some(interpreter) = &elf.interpreter {
+                println!("\tInterpreter: {}", interpreter);
+            }
+            if let Some(soname) = &elf.soname {
+                println!("\tSONAME: {}", soname);
+            }
+            for needed in &elf.needed {
+                println!("\tNEEDED: {}", needed);
+            }
+            for rpath in &elf.rpath {
+                println!("\tRPATH: {}", rpath);
+            }
+            for runpath in &elf.runpath {
+                println!("\tRUNPATH: {}", runpath);
+            }
+            if let Some(flags) = &elf.flags {
+                println!("\tFLAGS: {:?}", flags);
+            }
+            if let Some(flags) = &elf.flags_1 {
+                println!("\tFLAGS_1: {:?}", flags);
+            }
+            if let Some(init) = elf.init {
+                println!("\tINIT: {:#x}", init);
+            }
+            if let Some(fini) = elf.fini {
+                println!("\tFINI: {:#x}", fini);
+            }
+            if let Some(array) = &elf.init_array {
+                println!(
+                    "\tINIT_ARRAY: {:#x} ({:#x} bytes)",
+                    array.address, array.size
+                );
+            }
+            if let Some(array) = &elf.fini_array {
+                println!(
+                    "\tFINI_ARRAY: {:#x} ({:#x} bytes)",
+                    array.address, array.size
+                );
+            }
+        }
+
+        // Mach-O load commands
+        if let Some(macho) = &information.macho_info {
+            println!();
+            println!("Mach-O:");
+            if let Some(uuid) = &macho.uuid {
+                println!("\tUUID: {}", uuid);
+            }
+            for build_version in &macho.build_versions {
+                println!(
+                    "\tPlatform: {} (minos {}, sdk {})",
+                    build_version.platform, build_version.minos, build_version.sdk
+                );
+            }
+            if let Some(dylib) = &macho.id_dylib {
+                print_macho_dylib(dylib);
+            }
+            for dylib in &macho.dylibs {
+                print_macho_dylib(dylib);
+            }
+            for rpath in &macho.rpaths {
+                println!("\tLC_RPATH: {}", rpath);
+            }
+            println!("\tCode signature: {}", macho.has_code_signature);
+        }
+
         // Sections
         println!();
         println!("Sections:");
@@ -95,4 +261,198 @@ impl PrintOutput for Plain {
             println!();
         }
     }
+
+    fn print_verification(&self, verification: &Verification) {
+        for import in &verification.unresolved {
+            println!("Unresolved: {}", format_import(import));
+        }
+        for import in &verification.unattributed {
+            println!("Unattributed: {}", format_import(import));
+        }
+        if !verification.unattributed.is_empty() {
+            println!(
+                "{} of the imports could not be attributed, not all libraries of the binary were found.",
+                verification.unattributed.len()
+            );
+        }
+
+        if verification.checked == 0 {
+            println!("No imports from {} were found.", verification.library);
+            return;
+        }
+
+        println!(
+            "{} of {} imports from {} are unresolved.",
+            verification.unresolved.len(),
+            verification.checked,
+            verification.library
+        );
+    }
+}
+
+fn format_dependency(dependency: &Dependency) -> String {
+    let mut line = dependency.library.clone();
+
+    if let Some(host) = &dependency.api_set_host {
+        line.push_str(&format!(" -> {}", host));
+    }
+
+    match (&dependency.status, &dependency.path) {
+        (ResolutionStatus::Skipped, _) => {}
+        (_, Some(path)) => line.push_str(&format!(" => {}", path.display())),
+        (_, None) => line.push_str(" => not found"),
+    }
+
+    if let Some(rule) = dependency.search_rule {
+        let rule = match rule {
+            SearchRule::Path => "path",
+            SearchRule::UserDirectory => "given directory",
+            SearchRule::Loaded => "already loaded",
+            SearchRule::KnownDll => "KnownDLLs",
+            SearchRule::ApplicationDirectory => "application directory",
+            SearchRule::SystemDirectory => "system directory",
+            SearchRule::WindowsDirectory => "Windows directory",
+            SearchRule::CurrentDirectory => "current directory",
+            SearchRule::EnvironmentPath => "PATH",
+            SearchRule::Rpath => "RPATH",
+            SearchRule::LdLibraryPath => "LD_LIBRARY_PATH",
+            SearchRule::Runpath => "RUNPATH",
+            SearchRule::LdSoCache => "ld.so.cache",
+            SearchRule::DefaultDirectory => "default directory",
+            SearchRule::LoaderPath => "@loader_path",
+            SearchRule::ExecutablePath => "@executable_path",
+            SearchRule::DyldLibraryPath => "DYLD_LIBRARY_PATH",
+            SearchRule::DyldFallbackLibraryPath => "DYLD_FALLBACK_LIBRARY_PATH",
+        };
+        match &dependency.rpath {
+            Some(rpath) => line.push_str(&format!(" ({} {})", rule, rpath)),
+            None => line.push_str(&format!(" ({})", rule)),
+        }
+    }
+
+    if dependency.delay_load {
+        line.push_str(" (delay-loaded)");
+    }
+
+    line
+}
+
+/// Prints the node indented by its depth, followed by its children.
+fn print_tree_node(node: &TreeNode, depth: usize) {
+    let mut line = format!(
+        "{}{}",
+        "    ".repeat(depth),
+        format_dependency(&node.dependency)
+    );
+    match node.mark {
+        Some(TreeMark::Seen) => line.push_str(" [seen]"),
+        Some(TreeMark::Cycle) => line.push_str(" [cycle]"),
+        None => {}
+    }
+    println!("{}", line);
+
+    for child in &node.children {
+        print_tree_node(child, depth + 1);
+    }
+}
+
+fn format_export(export: &Export) -> String {
+    let mut line = format!("{:#018x}", export.address.unwrap_or_default());
+
+    if let Some(ordinal) = export.ordinal {
+        line.push_str(&format!(" @{}", ordinal));
+    }
+
+    line.push_str(&format!(
+        ": {} {}",
+        export.function, export.function_demangled
+    ));
+
+    if let Some(target) = &export.target {
+        line.push_str(&format!(" -> {}.", target.library));
+        match &target.forward {
+            ForwardType::Name(name) => line.push_str(name),
+            ForwardType::Ordinal(ordinal) => line.push_str(&ordinal.to_string()),
+        }
+
+        match &target.resolution {
+            Some(ForwardResolution::Resolved { library, address }) => {
+                line.push_str(&format!(" => {} {:#x}", library, address))
+            }
+            Some(ForwardResolution::Broken { library, forward }) => {
+                line.push_str(&format!(" => broken at {}.", library));
+                match forward {
+                    ForwardType::Name(name) => line.push_str(name),
+                    ForwardType::Ordinal(ordinal) => line.push_str(&ordinal.to_string()),
+                }
+            }
+            None => {}
+        }
+    }
+
+    if let Some(entry) = &export.import_library {
+        let name_type = match entry.name_type {
+            NameType::Ordinal => "ORDINAL",
+            NameType::Name => "NAME",
+            NameType::NoPrefix => "NOPREFIX",
+            NameType::Undecorate => "UNDECORATE",
+            NameType::ExportAs => "EXPORTAS",
+        };
+        line.push_str(&format!(
+            " in {} as {} ({})",
+            entry.library, entry.symbol, name_type
+        ));
+    }
+
+    line
+}
+
+fn format_import(import: &Import) -> String {
+    // ELF imports of unversioned symbols do not name their library.
+    let mut line = match import.library.as_str() {
+        "" => String::new(),
+        library => format!("{}: ", library),
+    };
+    match import.ordinal {
+        Some(ordinal) if import.function.is_empty() => line.push_str(&format!("@{}", ordinal)),
+        Some(ordinal) => line.push_str(&format!(
+            "@{} {} {}",
+            ordinal, import.function, import.function_demangled
+        )),
+        None => line.push_str(&format!(
+            "{} {}",
+            import.function, import.function_demangled
+        )),
+    }
+
+    if let Some(hint) = import.hint {
+        line.push_str(&format!(" hint {}", hint));
+    }
+    if let Some(iat_address) = import.iat_address {
+        line.push_str(&format!(" IAT {:#x}", iat_address));
+    }
+
+    if import.delay_load {
+        line.push_str(" (delay-loaded)");
+    }
+
+    line
+}
+
+fn print_macho_dylib(dylib: &MachODylib) {
+    println!(
+        "\t{}: {} (current {}, compatibility {})",
+        dylib.command, dylib.name, dylib.current_version, dylib.compatibility_version
+    );
+}
+
+fn print_difference<T>(difference: &Difference<T>, format: fn(&T) -> String) {
+    match difference {
+        Difference::Added(entry) => println!("+ {}", format(entry)),
+        Difference::Removed(entry) => println!("- {}", format(entry)),
+        Difference::Changed { old, new } => {
+            println!("~ {}", format(old));
+            println!("  => {}", format(new));
+        }
+    }
 }
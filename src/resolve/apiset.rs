@@ -0,0 +1,329 @@
+use std::{collections::HashMap, error::Error, fs, path::Path};
+
+use object::{Object, ObjectSection};
+
+/// The API set schema of Windows, which maps API set contracts, e.g.
+/// `api-ms-win-core-heap-l1-1-0.dll`, to the DLLs that implement them.
+pub struct ApiSetSchema {
+    version: u32,
+    /// The hosts of every API set, by the name the set is looked up with.
+    api_sets: HashMap<String, Vec<ApiSetHost>>,
+}
+
+/// A DLL that implements an API set.
+struct ApiSetHost {
+    /// The importing DLL this host is used for, `None` for the default host.
+    importer: Option<String>,
+    host: String,
+}
+
+impl ApiSetSchema {
+    /// Reads the schema from the `.apiset` section of an apisetschema.dll.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let binary_data = fs::read(path)?;
+        let object_file = object::File::parse(&*binary_data)?;
+        let section = object_file
+            .section_by_name(".apiset")
+            .ok_or_else(|| format!("{} has no .apiset section", path.display()))?;
+
+        Self::parse(section.data()?)
+    }
+
+    /// Parses the schema versions 2 (Windows 7), 4 (Windows 8.1) and 6 (Windows 10).
+    pub fn parse(data: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let version = read_u32(data, 0)?;
+
+        let mut schema = ApiSetSchema {
+            version,
+            api_sets: HashMap::new(),
+        };
+        match version {
+            2 => {
+                for index in 0..read_u32(data, 4)? {
+                    let entry = entry_offset(8, index, 12)?;
+                    let name = read_string(
+                        data,
+                        read_u32(data, entry)?,
+                        read_u32(data, add(entry, 4)?)?,
+                    )?;
+                    let values = read_u32(data, add(entry, 8)?)?;
+                    let hosts = read_hosts(data, add(values, 4)?, read_u32(data, values)?, 16)?;
+                    schema.insert(&name, hosts);
+                }
+            }
+            4 => {
+                for index in 0..read_u32(data, 12)? {
+                    let entry = entry_offset(16, index, 24)?;
+                    let name = read_string(
+                        data,
+                        read_u32(data, add(entry, 4)?)?,
+                        read_u32(data, add(entry, 8)?)?,
+                    )?;
+                    let values = read_u32(data, add(entry, 20)?)?;
+                    let hosts =
+                        read_hosts(data, add(values, 8)?, read_u32(data, add(values, 4)?)?, 20)?;
+                    schema.insert(&name, hosts);
+                }
+            }
+            6 => {
+                let entries = read_u32(data, 16)?;
+                for index in 0..read_u32(data, 12)? {
+                    let entry = entry_offset(entries, index, 24)?;
+                    // The hashed length, which covers the name up to its last hyphen.
+                    let name = read_string(
+                        data,
+                        read_u32(data, add(entry, 4)?)?,
+                        read_u32(data, add(entry, 12)?)?,
+                    )?;
+                    let hosts = read_hosts(
+                        data,
+                        read_u32(data, add(entry, 16)?)?,
+                        read_u32(data, add(entry, 20)?)?,
+                        20,
+                    )?;
+                    schema.api_sets.insert(name.to_ascii_lowercase(), hosts);
+                }
+            }
+            _ => return Err(format!("unsupported API set schema version {}", version).into()),
+        }
+
+        Ok(schema)
+    }
+
+    /// Returns the DLL that implements the API set for the given importer.
+    ///
+    /// Returns `None` if the library is no API set, or if the API set has no host.
+    pub fn host(&self, library: &str, importer: Option<&str>) -> Option<&str> {
+        let hosts = self.api_sets.get(&self.key(library)?)?;
+
+        // Hosts for a specific importer take precedence over the default host.
+        let importer_host = importer.and_then(|importer| {
+            hosts.iter().find(|host| {
+                host.importer
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(importer))
+            })
+        });
+
+        importer_host
+            .or_else(|| hosts.iter().find(|host| host.importer.is_none()))
+            .map(|host| host.host.as_str())
+    }
+
+    fn insert(&mut self, name: &str, hosts: Vec<ApiSetHost>) {
+        let name = name.to_ascii_lowercase();
+        let name = name.strip_prefix("api-").unwrap_or(&name);
+        self.api_sets.insert(name.to_owned(), hosts);
+    }
+
+    /// The name the API set is looked up with.
+    ///
+    /// Older schemas store the names without the `api-` prefix, newer ones without the minor version.
+    fn key(&self, library: &str) -> Option<String> {
+        let name = library.to_ascii_lowercase();
+        let name = name.strip_suffix(".dll").unwrap_or(&name);
+        if !name.starts_with("api-") && !name.starts_with("ext-") {
+            return None;
+        }
+
+        match self.version {
+            // Only the name up to the last hyphen is compared, which skips the minor version.
+            6 => name.rsplit_once('-').map(|(name, _)| name.to_owned()),
+            _ => Some(name.strip_prefix("api-").unwrap_or(name).to_owned()),
+        }
+    }
+}
+
+/// Reads the value entries of an API set, which start with the importer and the host name.
+fn read_hosts(
+    data: &[u8],
+    offset: u32,
+    count: u32,
+    entry_size: u32,
+) -> Result<Vec<ApiSetHost>, Box<dyn Error>> {
+    // Newer value entries start with flags.
+    let fields = if entry_size == 20 { 4 } else { 0 };
+
+    let mut hosts = Vec::<ApiSetHost>::new();
+    for index in 0..count {
+        let entry = add(entry_offset(offset, index, entry_size)?, fields)?;
+        let importer = read_string(
+            data,
+            read_u32(data, entry)?,
+            read_u32(data, add(entry, 4)?)?,
+        )?;
+        let host = read_string(
+            data,
+            read_u32(data, add(entry, 8)?)?,
+            read_u32(data, add(entry, 12)?)?,
+        )?;
+
+        // An empty host means that the API set is not implemented.
+        if host.is_empty() {
+            continue;
+        }
+
+        hosts.push(ApiSetHost {
+            importer: (!importer.is_empty()).then_some(importer),
+            host,
+        });
+    }
+
+    Ok(hosts)
+}
+
+const TRUNCATED: &str = "the API set schema is truncated";
+
+/// The offset of an entry in an array, offsets past the end of the schema are truncated.
+fn entry_offset(start: u32, index: u32, entry_size: u32) -> Result<u32, Box<dyn Error>> {
+    add(start, index.checked_mul(entry_size).ok_or(TRUNCATED)?)
+}
+
+fn add(offset: u32, length: u32) -> Result<u32, Box<dyn Error>> {
+    Ok(offset.checked_add(length).ok_or(TRUNCATED)?)
+}
+
+fn read_u32(data: &[u8], offset: u32) -> Result<u32, Box<dyn Error>> {
+    let bytes = data
+        .get(offset as usize..add(offset, 4)? as usize)
+        .ok_or(TRUNCATED)?;
+
+    Ok(u32::from_le_bytes(bytes.try_into()?))
+}
+
+/// Reads a UTF-16 string, the length is in bytes.
+fn read_string(data: &[u8], offset: u32, length: u32) -> Result<String, Box<dyn Error>> {
+    let bytes = data
+        .get(offset as usize..add(offset, length)? as usize)
+        .ok_or(TRUNCATED)?;
+
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect();
+    Ok(String::from_utf16_lossy(&units))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A schema under construction, with the header and tables at fixed offsets.
+    #[derive(Default)]
+    struct Schema {
+        data: Vec<u8>,
+    }
+
+    impl Schema {
+        fn put(&mut self, offset: u32, value: u32) {
+            let offset = offset as usize;
+            if self.data.len() < offset + 4 {
+                self.data.resize(offset + 4, 0);
+            }
+            self.data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        /// Appends the UTF-16 string and writes its offset and length.
+        fn put_string(&mut self, offset: u32, text: &str) {
+            let start = self.data.len().max(offset as usize + 8) as u32;
+            self.data.resize(start as usize, 0);
+            for unit in text.encode_utf16() {
+                self.data.extend_from_slice(&unit.to_le_bytes());
+            }
+            self.put(offset, start);
+            self.put(offset + 4, text.len() as u32 * 2);
+        }
+
+        /// Writes the importer and host of a value entry.
+        fn put_host(&mut self, offset: u32, importer: &str, host: &str) {
+            self.put_string(offset, importer);
+            self.put_string(offset + 8, host);
+        }
+    }
+
+    const HEAP: &str = "api-ms-win-core-heap-l1-1-0.dll";
+
+    fn assert_hosts(schema: &ApiSetSchema) {
+        assert_eq!(schema.host(HEAP, None), Some("kernel32.dll"));
+        assert_eq!(
+            schema.host(HEAP, Some("KERNEL32.DLL")),
+            Some("kernelbase.dll")
+        );
+        assert_eq!(schema.host("kernel32.dll", None), None);
+        assert_eq!(schema.host("api-ms-win-core-file-l1-1-0.dll", None), None);
+    }
+
+    #[test]
+    fn parses_version_2() {
+        let mut schema = Schema::default();
+        schema.data.resize(56, 0);
+        schema.put(0, 2);
+        schema.put(4, 1);
+        schema.put(16, 20);
+        schema.put(20, 2);
+        schema.put_host(40, "kernel32.dll", "kernelbase.dll");
+        schema.put_host(24, "", "kernel32.dll");
+        schema.put_string(8, "MS-Win-Core-Heap-L1-1-0");
+
+        assert_hosts(&ApiSetSchema::parse(&schema.data).unwrap());
+    }
+
+    #[test]
+    fn parses_version_4() {
+        let mut schema = Schema::default();
+        schema.data.resize(88, 0);
+        schema.put(0, 4);
+        schema.put(12, 1);
+        schema.put(36, 40);
+        schema.put(44, 2);
+        schema.put_host(52, "", "kernel32.dll");
+        schema.put_host(72, "kernel32.dll", "kernelbase.dll");
+        schema.put_string(20, "MS-Win-Core-Heap-L1-1-0");
+
+        assert_hosts(&ApiSetSchema::parse(&schema.data).unwrap());
+    }
+
+    #[test]
+    fn parses_version_6() {
+        let mut schema = Schema::default();
+        schema.data.resize(92, 0);
+        schema.put(0, 6);
+        schema.put(12, 1);
+        schema.put(16, 28);
+        schema.put(44, 52);
+        schema.put(48, 2);
+        schema.put_host(56, "", "kernel32.dll");
+        schema.put_host(76, "kernel32.dll", "kernelbase.dll");
+        // The hashed length leaves out the minor version.
+        schema.put_string(32, "api-ms-win-core-heap-l1-1-0");
+        schema.put(40, "api-ms-win-core-heap-l1-1".len() as u32 * 2);
+
+        let parsed = ApiSetSchema::parse(&schema.data).unwrap();
+        assert_hosts(&parsed);
+        assert_eq!(
+            parsed.host("api-ms-win-core-heap-l1-1-2.dll", None),
+            Some("kernel32.dll")
+        );
+    }
+
+    #[test]
+    fn truncated_schema_is_an_error() {
+        let mut schema = Schema::default();
+        schema.put(0, 2);
+        schema.put(4, 1);
+
+        let error = ApiSetSchema::parse(&schema.data).err().unwrap();
+        assert_eq!(error.to_string(), TRUNCATED);
+    }
+
+    #[test]
+    fn offsets_past_4_gib_are_an_error() {
+        let mut schema = Schema::default();
+        schema.put(0, 6);
+        schema.put(12, 2);
+        schema.put(16, u32::MAX - 2);
+
+        let error = ApiSetSchema::parse(&schema.data).err().unwrap();
+        assert_eq!(error.to_string(), TRUNCATED);
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use object::{elf::DF_1_NODEFLIB, Architecture};
+
+use crate::elf;
+
+use super::{follow_links, in_sysroot};
+
+/// The directories an ELF object adds to the search for its own dependencies,
+/// as written and with the dynamic string tokens expanded.
+#[derive(Default)]
+pub struct ObjectSearchPaths {
+    pub rpath: Vec<(String, PathBuf)>,
+    pub runpath: Vec<(String, PathBuf)>,
+    /// Set by `DF_1_NODEFLIB`, which skips the cache and the default directories.
+    pub no_default_directories: bool,
+}
+
+impl ObjectSearchPaths {
+    /// Reads the search paths from the dynamic section.
+    ///
+    /// Absolute directories are taken from the sysroot, those relative to `$ORIGIN` are not.
+    pub fn read(
+        path: &Path,
+        binary_data: &[u8],
+        architecture: Architecture,
+        sysroot: Option<&Path>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let Some(dynamic) = elf::dynamic(binary_data)? else {
+            return Ok(ObjectSearchPaths::default());
+        };
+
+        // $ORIGIN is the directory of the object, with symbolic links resolved.
+        let origin = follow_links(sysroot, path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let expand = |directories: Vec<String>| -> Vec<(String, PathBuf)> {
+            directories
+                .into_iter()
+                .map(|directory| {
+                    let expanded = expand_tokens(&directory, &origin, architecture, sysroot);
+                    let expanded = if directory.starts_with('/') {
+                        in_sysroot(sysroot, expanded)
+                    } else {
+                        expanded
+                    };
+                    (directory, expanded)
+                })
+                .collect()
+        };
+
+        Ok(ObjectSearchPaths {
+            rpath: expand(dynamic.rpath),
+            runpath: expand(dynamic.runpath),
+            no_default_directories: dynamic
+                .flags_1
+                .is_some_and(|flags| flags & DF_1_NODEFLIB != 0),
+        })
+    }
+}
+
+/// Replaces `$ORIGIN`, `$LIB` and `$PLATFORM`, also in the `${NAME}` form.
+pub fn expand_tokens(
+    directory: &str,
+    origin: &Path,
+    architecture: Architecture,
+    sysroot: Option<&Path>,
+) -> PathBuf {
+    let mut expanded = directory.to_owned();
+    for (token, value) in [
+        ("ORIGIN", origin.to_string_lossy().into_owned()),
+        ("LIB", lib_directory(architecture, sysroot)),
+        ("PLATFORM", platform(architecture).to_owned()),
+    ] {
+        expanded = expanded
+            .replace(&format!("${{{}}}", token), &value)
+            .replace(&format!("${}", token), &value);
+    }
+
+    PathBuf::from(expanded)
+}
+
+/// The value of `$LIB`, the directory the system keeps its libraries in.
+fn lib_directory(architecture: Architecture, sysroot: Option<&Path>) -> String {
+    if let Some(triplet) = multiarch_triplet(architecture) {
+        let directory = format!("lib/{}", triplet);
+        if sysroot.unwrap_or(Path::new("/")).join(&directory).is_dir() {
+            return directory;
+        }
+    }
+
+    match architecture.address_size().map(|size| size.bytes()) {
+        Some(8) => "lib64".to_owned(),
+        _ => "lib".to_owned(),
+    }
+}
+
+/// The value of `$PLATFORM`, the processor type the kernel reports in `AT_PLATFORM`.
+fn platform(architecture: Architecture) -> &'static str {
+    match architecture {
+        Architecture::Aarch64 => "aarch64",
+        Architecture::Arm => "v7l",
+        Architecture::I386 => "i686",
+        Architecture::PowerPc64 => "power8",
+        Architecture::Riscv64 => "riscv64",
+        Architecture::S390x => "z900",
+        Architecture::X86_64 => "x86_64",
+        _ => "",
+    }
+}
+
+/// The multiarch tuple of Debian based distributions.
+pub fn multiarch_triplet(architecture: Architecture) -> Option<&'static str> {
+    match architecture {
+        Architecture::Aarch64 => Some("aarch64-linux-gnu"),
+        Architecture::Arm => Some("arm-linux-gnueabihf"),
+        Architecture::I386 => Some("i386-linux-gnu"),
+        Architecture::PowerPc64 => Some("powerpc64le-linux-gnu"),
+        Architecture::Riscv64 => Some("riscv64-linux-gnu"),
+        Architecture::S390x => Some("s390x-linux-gnu"),
+        Architecture::X86_64 => Some("x86_64-linux-gnu"),
+        _ => None,
+    }
+}
+
+/// The libraries listed in the `/etc/ld.so.cache` of the sysroot, as pairs of name and path.
+///
+/// The cache holds the libraries of every architecture, the caller has to pick the
+/// entry that matches the binary.
+pub fn read_ld_so_cache(sysroot: Option<&Path>) -> Result<Vec<(String, PathBuf)>, Box<dyn Error>> {
+    let path = in_sysroot(sysroot, PathBuf::from("/etc/ld.so.cache"));
+    Ok(read_ld_so_cache_entries(&follow_links(sysroot, &path))?
+        .into_iter()
+        .map(|(name, path)| (name, in_sysroot(sysroot, path)))
+        .collect())
+}
+
+fn read_ld_so_cache_entries(path: &Path) -> Result<Vec<(String, PathBuf)>, Box<dyn Error>> {
+    let data = fs::read(path)?;
+    parse_ld_so_cache(&data).map_err(|error| format!("{}: {}", path.display(), error).into())
+}
+
+/// Parses the old format of libc5, the new format of glibc, or the old one followed by the new one.
+fn parse_ld_so_cache(data: &[u8]) -> Result<Vec<(String, PathBuf)>, Box<dyn Error>> {
+    const OLD_MAGIC: &[u8] = b"ld.so-1.7.0";
+    const NEW_MAGIC: &[u8] = b"glibc-ld.so.cache1.1";
+
+    // The old format may be followed by the new one, which is preferred.
+    let has_new_format = |start: usize| {
+        data.get(start..)
+            .is_some_and(|data| data.starts_with(NEW_MAGIC))
+    };
+    let mut new_start = 0;
+    if data.starts_with(OLD_MAGIC) {
+        let count = read_u32(data, 12)? as usize;
+        let strings = entry_offset(16, count, 12)?;
+        new_start = add(strings, 7)? & !7;
+
+        if !has_new_format(new_start) {
+            return (0..count)
+                .map(|index| read_cache_entry(data, entry_offset(16, index, 12)?, strings))
+                .collect();
+        }
+    }
+
+    if !has_new_format(new_start) {
+        return Err("the file is no ld.so.cache".into());
+    }
+
+    // Entries of the new format are 24 bytes, the strings are relative to its header.
+    let count = read_u32(data, add(new_start, 20)?)? as usize;
+    let entries = add(new_start, 48)?;
+    (0..count)
+        .map(|index| read_cache_entry(data, entry_offset(entries, index, 24)?, new_start))
+        .collect()
+}
+
+/// Reads the name and path of an entry, which follow its flags.
+fn read_cache_entry(
+    data: &[u8],
+    entry: usize,
+    strings: usize,
+) -> Result<(String, PathBuf), Box<dyn Error>> {
+    let key = read_string(
+        data,
+        add(strings, read_u32(data, add(entry, 4)?)? as usize)?,
+    )?;
+    let value = read_string(
+        data,
+        add(strings, read_u32(data, add(entry, 8)?)? as usize)?,
+    )?;
+
+    Ok((key, PathBuf::from(value)))
+}
+
+const TRUNCATED: &str = "the ld.so.cache is truncated";
+
+/// The offset of an entry in an array, offsets that do not fit are truncated.
+fn entry_offset(start: usize, index: usize, entry_size: usize) -> Result<usize, Box<dyn Error>> {
+    add(start, index.checked_mul(entry_size).ok_or(TRUNCATED)?)
+}
+
+fn add(offset: usize, length: usize) -> Result<usize, Box<dyn Error>> {
+    Ok(offset.checked_add(length).ok_or(TRUNCATED)?)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Box<dyn Error>> {
+    let bytes = data.get(offset..add(offset, 4)?).ok_or(TRUNCATED)?;
+
+    Ok(u32::from_ne_bytes(bytes.try_into()?))
+}
+
+fn read_string(data: &[u8], offset: usize) -> Result<String, Box<dyn Error>> {
+    let bytes = data.get(offset..).ok_or(TRUNCATED)?;
+    let end = bytes.iter().position(|byte| *byte == 0).ok_or(TRUNCATED)?;
+
+    Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARIES: [(&str, &str); 2] = [
+        ("libc.so.6", "/lib/x86_64-linux-gnu/libc.so.6"),
+        ("libm.so.6", "/lib/x86_64-linux-gnu/libm.so.6"),
+    ];
+
+    fn push_u32(data: &mut Vec<u8>, value: u32) {
+        data.extend_from_slice(&value.to_ne_bytes());
+    }
+
+    /// The strings of the libraries and their offsets, starting at the given offset.
+    fn strings(start: usize) -> (Vec<u8>, Vec<(u32, u32)>) {
+        let mut strings = Vec::<u8>::new();
+        let mut offsets = Vec::<(u32, u32)>::new();
+        for (name, path) in LIBRARIES {
+            let key = (start + strings.len()) as u32;
+            strings.extend_from_slice(name.as_bytes());
+            strings.push(0);
+            let value = (start + strings.len()) as u32;
+            strings.extend_from_slice(path.as_bytes());
+            strings.push(0);
+            offsets.push((key, value));
+        }
+        (strings, offsets)
+    }
+
+    fn old_format() -> Vec<u8> {
+        let mut data = b"ld.so-1.7.0\0".to_vec();
+        push_u32(&mut data, LIBRARIES.len() as u32);
+        let (strings, offsets) = strings(0);
+        for (key, value) in offsets {
+            push_u32(&mut data, 1);
+            push_u32(&mut data, key);
+            push_u32(&mut data, value);
+        }
+        data.extend(strings);
+        data
+    }
+
+    fn new_format() -> Vec<u8> {
+        let mut data = b"glibc-ld.so.cache1.1".to_vec();
+        push_u32(&mut data, LIBRARIES.len() as u32);
+        data.resize(48, 0);
+        let (strings, offsets) = strings(48 + LIBRARIES.len() * 24);
+        for (key, value) in offsets {
+            push_u32(&mut data, 0x303);
+            push_u32(&mut data, key);
+            push_u32(&mut data, value);
+            data.resize(data.len() + 12, 0);
+        }
+        data.extend(strings);
+        data
+    }
+
+    fn expected() -> Vec<(String, PathBuf)> {
+        LIBRARIES
+            .iter()
+            .map(|(name, path)| (name.to_string(), PathBuf::from(path)))
+            .collect()
+    }
+
+    #[test]
+    fn parses_old_format() {
+        assert_eq!(parse_ld_so_cache(&old_format()).unwrap(), expected());
+    }
+
+    #[test]
+    fn parses_new_format() {
+        assert_eq!(parse_ld_so_cache(&new_format()).unwrap(), expected());
+    }
+
+    #[test]
+    fn prefers_new_format_after_old_format() {
+        // The old entries are left empty, only the new ones can be read.
+        let mut data = b"ld.so-1.7.0\0".to_vec();
+        push_u32(&mut data, 1);
+        data.resize(32, 0);
+        data.extend(new_format());
+
+        let entries = parse_ld_so_cache(&data).unwrap();
+        assert_eq!(entries.len(), LIBRARIES.len());
+    }
+
+    #[test]
+    fn truncated_cache_is_an_error() {
+        let data = new_format();
+        let error = parse_ld_so_cache(&data[..60]).unwrap_err();
+        assert_eq!(error.to_string(), TRUNCATED);
+
+        let mut data = b"ld.so-1.7.0\0".to_vec();
+        push_u32(&mut data, u32::MAX);
+        assert!(parse_ld_so_cache(&data).is_err());
+    }
+
+    #[test]
+    fn other_files_are_no_cache() {
+        assert!(parse_ld_so_cache(b"\x7fELF").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    env,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use crate::macho;
+
+use super::in_sysroot;
+
+/// Reads the LC_RPATH entries, as written and with `@loader_path` and `@executable_path` expanded.
+///
+/// Absolute entries are taken from the sysroot.
+pub fn rpaths(
+    path: &Path,
+    binary_data: &[u8],
+    executable_directory: &Path,
+    sysroot: Option<&Path>,
+) -> Result<Vec<(String, PathBuf)>, Box<dyn Error>> {
+    let Some(load_commands) = macho::load_commands(binary_data)? else {
+        return Ok(Vec::new());
+    };
+
+    let loader_directory = path.parent().unwrap_or(Path::new(""));
+    Ok(load_commands
+        .rpaths
+        .into_iter()
+        .map(|rpath| {
+            let expanded = expand_prefix(&rpath, loader_directory, executable_directory);
+            let expanded = if rpath.starts_with('/') {
+                in_sysroot(sysroot, expanded)
+            } else {
+                expanded
+            };
+            (rpath, expanded)
+        })
+        .collect())
+}
+
+/// Replaces a leading `@loader_path` or `@executable_path` with the directory it stands for.
+pub fn expand_prefix(path: &str, loader_directory: &Path, executable_directory: &Path) -> PathBuf {
+    for (prefix, directory) in [
+        ("@loader_path", loader_directory),
+        ("@executable_path", executable_directory),
+    ] {
+        if let Some(rest) = path.strip_prefix(prefix) {
+            return directory.join(rest.trim_start_matches('/'));
+        }
+    }
+
+    PathBuf::from(path)
+}
+
+/// The directories of `DYLD_FALLBACK_LIBRARY_PATH`, which dyld searches when everything else failed.
+///
+/// The environment of the host is ignored for a sysroot, which only gets the default directories.
+pub fn fallback_library_path(sysroot: Option<&Path>) -> Vec<PathBuf> {
+    let environment = |name: &str| env::var_os(name).filter(|_| sysroot.is_none());
+    let directories = match environment("DYLD_FALLBACK_LIBRARY_PATH") {
+        Some(path) => env::split_paths(&path).collect(),
+        None => {
+            let mut directories = Vec::<PathBuf>::new();
+            if let Some(home) = environment("HOME") {
+                directories.push(Path::new(&home).join("lib"));
+            }
+            directories.extend(["/usr/local/lib", "/usr/lib"].map(PathBuf::from));
+            directories
+        }
+    };
+
+    directories
+        .into_iter()
+        .map(|directory| in_sysroot(sysroot, directory))
+        .collect()
+}
@@ -0,0 +1,719 @@
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use object::{Architecture, FileKind, Object};
+use serde::Serialize;
+
+use crate::cmd::list::data::{Dependency, ResolutionStatus};
+use crate::{binary, cli::GlobalOptions, import_library};
+
+use self::{apiset::ApiSetSchema, elf::ObjectSearchPaths, windows::WindowsConfig};
+
+pub mod apiset;
+mod elf;
+mod macho;
+mod windows;
+
+/// The loader conventions that are used to locate dependencies.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Format {
+    Elf,
+    MachO,
+    Pe,
+    Other,
+}
+
+impl Format {
+    fn from_kind(kind: FileKind) -> Self {
+        match kind {
+            FileKind::Elf32 | FileKind::Elf64 => Format::Elf,
+            FileKind::MachO32 | FileKind::MachO64 => Format::MachO,
+            FileKind::Pe32 | FileKind::Pe64 => Format::Pe,
+            _ => Format::Other,
+        }
+    }
+}
+
+/// The rule of the loader's search order that located a library.
+#[derive(Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchRule {
+    /// The library was named with a path and not searched for.
+    Path,
+    /// A directory given on the command line.
+    UserDirectory,
+    /// The library is already loaded, e.g. a DLL importing from the executable.
+    Loaded,
+    /// A known DLL, which is always loaded from the system directory.
+    KnownDll,
+    /// The directory of the application.
+    ApplicationDirectory,
+    /// The Windows system directory.
+    SystemDirectory,
+    /// The Windows directory.
+    WindowsDirectory,
+    /// The current directory of the process.
+    CurrentDirectory,
+    /// A directory of the `PATH` environment variable.
+    EnvironmentPath,
+    /// The `DT_RPATH` of the objects that loaded the library, or an `LC_RPATH` of Mach-O.
+    Rpath,
+    /// A directory of the `LD_LIBRARY_PATH` environment variable.
+    LdLibraryPath,
+    /// The `DT_RUNPATH` of the importing object.
+    Runpath,
+    /// The `/etc/ld.so.cache` of the dynamic loader.
+    LdSoCache,
+    /// A default directory of the dynamic loader, e.g. `/usr/lib`.
+    DefaultDirectory,
+    /// An install name relative to `@loader_path`.
+    LoaderPath,
+    /// An install name relative to `@executable_path`.
+    ExecutablePath,
+    /// A directory of the `DYLD_LIBRARY_PATH` environment variable.
+    DyldLibraryPath,
+    /// A directory of the `DYLD_FALLBACK_LIBRARY_PATH` environment variable, or its default.
+    DyldFallbackLibraryPath,
+}
+
+/// Where a library was found and how the loader got there.
+pub struct Location {
+    pub path: PathBuf,
+    pub rule: SearchRule,
+    /// The rpath entry that located the library, as it is written in the binary.
+    pub rpath: Option<String>,
+}
+
+/// An object that loads libraries, with the search paths it contributes to their lookup.
+#[derive(Clone, Default)]
+pub struct Loader {
+    path: PathBuf,
+    /// The rpath entries, as written and expanded, followed by those of the objects
+    /// further up the loading chain.
+    rpath: Vec<(String, PathBuf)>,
+    runpath: Vec<(String, PathBuf)>,
+    /// Set by `DF_1_NODEFLIB`, which skips the cache and the default directories.
+    no_default_directories: bool,
+}
+
+/// Maps library names, as they appear in a binary, to files on disk.
+pub struct Resolver {
+    format: Format,
+    architecture: Architecture,
+    search_paths: Vec<(PathBuf, SearchRule)>,
+    api_sets: Option<ApiSetSchema>,
+    /// The binary, which is at the start of every loading chain.
+    executable: Loader,
+    /// The directories of `LD_LIBRARY_PATH` or `DYLD_LIBRARY_PATH`.
+    library_path: Vec<PathBuf>,
+    fallback_library_path: Vec<PathBuf>,
+    ld_so_cache: Vec<(String, PathBuf)>,
+    windows: WindowsConfig,
+    /// The root file system that absolute search paths refer to, `None` for the host.
+    sysroot: Option<PathBuf>,
+}
+
+impl Resolver {
+    /// Creates a resolver for the dependencies of the given binary.
+    pub fn new(
+        file: &Path,
+        binary_data: &[u8],
+        global: &GlobalOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        if import_library::is_import_library(binary_data) {
+            return Err(format!("{} is an import library, not an image", file.display()).into());
+        }
+
+        let object_file = object::File::parse(binary_data)?;
+        let format = Format::from_kind(FileKind::parse(binary_data)?);
+        let architecture = object_file.architecture();
+
+        let mut search_paths = Vec::<(PathBuf, SearchRule)>::new();
+        let mut library_path = Vec::<PathBuf>::new();
+        let mut fallback_library_path = Vec::<PathBuf>::new();
+        let mut ld_so_cache = Vec::<(String, PathBuf)>::new();
+        let mut windows = WindowsConfig::default();
+        let sysroot = global.sysroot.as_deref();
+        // The environment describes the host, it has no meaning for a foreign root file system.
+        let environment_paths = |name: &str| -> Vec<PathBuf> {
+            match env::var_os(name) {
+                Some(path) if sysroot.is_none() => env::split_paths(&path).collect(),
+                _ => Vec::new(),
+            }
+        };
+        match format {
+            Format::Pe => {
+                if let Some(path) = &global.windows_config {
+                    windows = WindowsConfig::load(path, sysroot)?;
+                }
+
+                // The directory from which the application was loaded.
+                if let Some(directory) = file.parent() {
+                    search_paths.push((directory.to_path_buf(), SearchRule::ApplicationDirectory));
+                }
+                let current_directory = windows
+                    .current_directory
+                    .clone()
+                    .map(|directory| (directory, SearchRule::CurrentDirectory));
+                if !windows.safe_dll_search_mode {
+                    search_paths.extend(current_directory.clone());
+                }
+                search_paths.extend(
+                    windows
+                        .system_directory
+                        .clone()
+                        .map(|directory| (directory, SearchRule::SystemDirectory)),
+                );
+                search_paths.extend(
+                    windows
+                        .windows_directory
+                        .clone()
+                        .map(|directory| (directory, SearchRule::WindowsDirectory)),
+                );
+                if windows.safe_dll_search_mode {
+                    search_paths.extend(current_directory);
+                }
+                let path = match &windows.path {
+                    Some(path) => path.clone(),
+                    None => environment_paths("PATH"),
+                };
+                search_paths.extend(
+                    path.into_iter()
+                        .map(|directory| (directory, SearchRule::EnvironmentPath)),
+                );
+            }
+            Format::Elf => {
+                library_path = environment_paths("LD_LIBRARY_PATH");
+                // A missing cache is not an error, the loader continues with the default directories.
+                ld_so_cache = elf::read_ld_so_cache(sysroot).unwrap_or_default();
+                search_paths.extend(default_elf_directories(architecture).into_iter().map(
+                    |directory| (in_sysroot(sysroot, directory), SearchRule::DefaultDirectory),
+                ));
+            }
+            Format::MachO => {
+                library_path = environment_paths("DYLD_LIBRARY_PATH");
+                fallback_library_path = macho::fallback_library_path(sysroot);
+            }
+            Format::Other => {}
+        }
+
+        let api_sets = match (&global.api_set_schema, format) {
+            (Some(path), Format::Pe) => Some(ApiSetSchema::load(path)?),
+            _ => None,
+        };
+
+        let mut resolver = Resolver {
+            format,
+            architecture,
+            search_paths,
+            api_sets,
+            executable: Loader {
+                path: file.to_path_buf(),
+                ..Loader::default()
+            },
+            library_path,
+            fallback_library_path,
+            ld_so_cache,
+            windows,
+            sysroot: sysroot.map(Path::to_path_buf),
+        };
+        resolver.executable = resolver.loader(file, binary_data, None)?;
+
+        Ok(resolver)
+    }
+
+    /// The binary, as the loader of its own dependencies.
+    pub fn executable(&self) -> &Loader {
+        &self.executable
+    }
+
+    /// Reads the search paths the given object contributes to the lookup of its dependencies.
+    ///
+    /// `parent` is the object that loaded it, `None` for the binary itself.
+    pub fn loader(
+        &self,
+        path: &Path,
+        binary_data: &[u8],
+        parent: Option<&Loader>,
+    ) -> Result<Loader, Box<dyn Error>> {
+        let mut loader = Loader {
+            path: path.to_path_buf(),
+            ..Loader::default()
+        };
+
+        match self.format {
+            Format::Elf => {
+                let own = ObjectSearchPaths::read(
+                    path,
+                    binary_data,
+                    self.architecture,
+                    self.sysroot.as_deref(),
+                )?;
+                // A DT_RPATH is ignored as soon as the same object has a DT_RUNPATH.
+                if own.runpath.is_empty() {
+                    loader.rpath = own.rpath;
+                }
+                loader.runpath = own.runpath;
+                loader.no_default_directories = own.no_default_directories;
+            }
+            Format::MachO => {
+                let executable_directory = self.executable.path.parent().unwrap_or(Path::new(""));
+                loader.rpath = macho::rpaths(
+                    path,
+                    binary_data,
+                    executable_directory,
+                    self.sysroot.as_deref(),
+                )?;
+            }
+            Format::Pe | Format::Other => {}
+        }
+
+        // The rpath entries of the objects up the loading chain are searched as well.
+        if let Some(parent) = parent {
+            loader.rpath.extend(parent.rpath.iter().cloned());
+        }
+
+        Ok(loader)
+    }
+
+    /// Searches the given directories before the default search paths.
+    pub fn prepend_search_paths(&mut self, directories: &[PathBuf]) {
+        self.search_paths.splice(
+            0..0,
+            directories
+                .iter()
+                .map(|directory| (directory.clone(), SearchRule::UserDirectory)),
+        );
+    }
+
+    /// Searches the given directories after all other search paths, as a last resort.
+    pub fn append_search_paths(&mut self, directories: &[PathBuf]) {
+        self.search_paths.extend(
+            directories
+                .iter()
+                .map(|directory| (directory.clone(), SearchRule::UserDirectory)),
+        );
+    }
+
+    /// Returns the file the given library of the binary resolves to.
+    pub fn resolve(&self, library: &str) -> Option<PathBuf> {
+        self.locate(library, &self.executable)
+            .map(|location| location.path)
+    }
+
+    /// Returns where the library, as it is loaded by the given object, resolves to.
+    pub fn locate(&self, library: &str, loader: &Loader) -> Option<Location> {
+        let candidates = match self.format {
+            Format::MachO => self.dyld_candidates(library, loader),
+            // Libraries given with a path are not searched for.
+            _ if library.contains('/') => vec![Location {
+                path: in_sysroot(self.sysroot.as_deref(), PathBuf::from(library)),
+                rule: SearchRule::Path,
+                rpath: None,
+            }],
+            Format::Pe if self.windows.is_known_dll(library) => {
+                match &self.windows.system_directory {
+                    Some(directory) => {
+                        return self
+                            .find_in_directory(directory, library)
+                            .filter(|path| self.is_compatible(path))
+                            .map(|path| Location {
+                                path,
+                                rule: SearchRule::KnownDll,
+                                rpath: None,
+                            });
+                    }
+                    None => Vec::new(),
+                }
+            }
+            Format::Elf => self.ld_so_candidates(library, loader),
+            Format::Pe | Format::Other => Vec::new(),
+        };
+
+        // DF_1_NODEFLIB leaves out the default directories, which are the only search paths of ELF.
+        let search_paths = match self.format {
+            Format::Elf if loader.no_default_directories => &[],
+            _ => self.search_paths.as_slice(),
+        };
+
+        // Directories are searched for the leaf name of an install name, like DYLD_LIBRARY_PATH.
+        let file_name = match self.format {
+            Format::MachO => library.rsplit('/').next().unwrap_or(library),
+            _ => library,
+        };
+
+        candidates
+            .into_iter()
+            .chain(search_paths.iter().filter_map(|(directory, rule)| {
+                self.find_in_directory(directory, file_name)
+                    .map(|path| Location {
+                        path,
+                        rule: *rule,
+                        rpath: None,
+                    })
+            }))
+            .find(|location| self.is_compatible(&location.path))
+    }
+
+    /// The files glibc tries before its default directories, in order.
+    fn ld_so_candidates(&self, library: &str, loader: &Loader) -> Vec<Location> {
+        let candidate = |(rpath, directory): &(String, PathBuf), rule| Location {
+            path: directory.join(library),
+            rule,
+            rpath: Some(rpath.clone()),
+        };
+
+        let mut candidates = Vec::<Location>::new();
+        if loader.runpath.is_empty() {
+            candidates.extend(
+                loader
+                    .rpath
+                    .iter()
+                    .map(|entry| candidate(entry, SearchRule::Rpath)),
+            );
+        }
+        for directory in &self.library_path {
+            candidates.push(Location {
+                path: directory.join(library),
+                rule: SearchRule::LdLibraryPath,
+                rpath: None,
+            });
+        }
+        candidates.extend(
+            loader
+                .runpath
+                .iter()
+                .map(|entry| candidate(entry, SearchRule::Runpath)),
+        );
+
+        if !loader.no_default_directories {
+            for (name, path) in &self.ld_so_cache {
+                if name == library {
+                    candidates.push(Location {
+                        path: path.clone(),
+                        rule: SearchRule::LdSoCache,
+                        rpath: None,
+                    });
+                }
+            }
+        }
+
+        candidates
+    }
+
+    /// The files dyld tries for an install name, in order.
+    fn dyld_candidates(&self, library: &str, loader: &Loader) -> Vec<Location> {
+        let leaf_name = library.rsplit('/').next().unwrap_or(library);
+        let location = |path: PathBuf, rule| Location {
+            path,
+            rule,
+            rpath: None,
+        };
+
+        // DYLD_LIBRARY_PATH overrides the install name with the leaf name.
+        let mut candidates: Vec<Location> = self
+            .library_path
+            .iter()
+            .map(|directory| location(directory.join(leaf_name), SearchRule::DyldLibraryPath))
+            .collect();
+
+        if let Some(rest) = library.strip_prefix("@rpath/") {
+            for (rpath, directory) in &loader.rpath {
+                candidates.push(Location {
+                    path: directory.join(rest),
+                    rule: SearchRule::Rpath,
+                    rpath: Some(rpath.clone()),
+                });
+            }
+        } else if library.starts_with("@loader_path") || library.starts_with("@executable_path") {
+            let loader_directory = loader.path.parent().unwrap_or(Path::new(""));
+            let executable_directory = self.executable.path.parent().unwrap_or(Path::new(""));
+            let rule = if library.starts_with("@loader_path") {
+                SearchRule::LoaderPath
+            } else {
+                SearchRule::ExecutablePath
+            };
+            candidates.push(location(
+                macho::expand_prefix(library, loader_directory, executable_directory),
+                rule,
+            ));
+        } else {
+            candidates.push(location(
+                in_sysroot(self.sysroot.as_deref(), PathBuf::from(library)),
+                SearchRule::Path,
+            ));
+        }
+
+        candidates.extend(self.fallback_library_path.iter().map(|directory| {
+            location(
+                directory.join(leaf_name),
+                SearchRule::DyldFallbackLibraryPath,
+            )
+        }));
+
+        candidates
+    }
+
+    /// Returns the DLL that implements the given API set, if an API set schema was given.
+    pub fn api_set_host(&self, library: &str, importer: Option<&str>) -> Option<String> {
+        self.api_sets
+            .as_ref()?
+            .host(library, importer)
+            .map(str::to_owned)
+    }
+
+    /// Returns the name under which the loader identifies the library.
+    ///
+    /// Windows resolves library names case insensitive.
+    pub fn normalize(&self, library: &str) -> String {
+        match self.format {
+            Format::Pe => library.to_ascii_lowercase(),
+            _ => library.to_owned(),
+        }
+    }
+
+    /// Returns the name under which the loader identifies the library the importer asks for.
+    ///
+    /// API sets are no files, the loader redirects them to their host.
+    pub fn loaded_name(&self, library: &str, importer: &str) -> String {
+        match self.api_set_host(library, Some(importer)) {
+            Some(host) => self.normalize(&host),
+            None => self.normalize(library),
+        }
+    }
+
+    /// Locates the dependency as the given object loads it and records where it was found.
+    ///
+    /// A library with the name of the binary is the binary itself, which is loaded first.
+    pub fn locate_dependency(
+        &self,
+        dependency: &mut Dependency,
+        importer: &str,
+        loader: &Loader,
+    ) -> Option<Location> {
+        dependency.api_set_host = self.api_set_host(&dependency.library, Some(importer));
+        let library = dependency
+            .api_set_host
+            .as_deref()
+            .unwrap_or(&dependency.library);
+
+        let executable_name = self
+            .executable
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let location = if self.normalize(library) == self.normalize(&executable_name) {
+            Some(Location {
+                path: self.executable.path.clone(),
+                rule: SearchRule::Loaded,
+                rpath: None,
+            })
+        } else {
+            self.locate(library, loader)
+        };
+
+        match &location {
+            Some(location) => {
+                dependency.status = ResolutionStatus::Found;
+                dependency.path = Some(location.path.clone());
+                dependency.search_rule = Some(location.rule);
+                dependency.rpath = location.rpath.clone();
+            }
+            None => dependency.status = ResolutionStatus::NotFound,
+        }
+
+        location
+    }
+
+    /// Reads the part of the library that matches the architecture of the binary.
+    pub fn load(&self, path: &Path) -> Result<Vec<u8>, Box<dyn Error>> {
+        let binary_data = fs::read(follow_links(self.sysroot.as_deref(), path))?;
+        match binary::slice_for(&binary_data, self.architecture) {
+            Some(slice) => Ok(slice.to_vec()),
+            None => Err(format!("{} does not match the architecture", path.display()).into()),
+        }
+    }
+
+    /// Loaders skip libraries that were built for another architecture.
+    fn is_compatible(&self, path: &Path) -> bool {
+        let Ok(binary_data) = fs::read(follow_links(self.sysroot.as_deref(), path)) else {
+            return false;
+        };
+
+        binary::slice_for(&binary_data, self.architecture).is_some()
+    }
+
+    /// Looks for the library in the given directory.
+    ///
+    /// Windows file systems are case insensitive, so PE libraries are matched ignoring the case.
+    fn find_in_directory(&self, directory: &Path, library: &str) -> Option<PathBuf> {
+        let is_file = |path: &Path| follow_links(self.sysroot.as_deref(), path).is_file();
+
+        let path = directory.join(library);
+        if is_file(&path) {
+            return Some(path);
+        }
+
+        if self.format != Format::Pe {
+            return None;
+        }
+
+        fs::read_dir(directory)
+            .ok()?
+            .filter_map(Result::ok)
+            .find(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case(library)
+            })
+            .map(|entry| entry.path())
+            .filter(|path| is_file(path))
+    }
+}
+
+/// The directories that any loader searches by default, for commands that look for
+/// libraries without a binary to start from.
+///
+/// Only directories that exist are returned, each of them once.
+pub fn default_search_directories(global: &GlobalOptions) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let sysroot = global.sysroot.as_deref();
+    // The environment describes the host, it has no meaning for a foreign root file system.
+    let environment_paths = |name: &str| -> Vec<PathBuf> {
+        match env::var_os(name) {
+            Some(path) if sysroot.is_none() => env::split_paths(&path).collect(),
+            _ => Vec::new(),
+        }
+    };
+
+    let mut directories = Vec::<PathBuf>::new();
+
+    // ELF: the cache holds libraries outside the default directories, e.g. from `ld.so.conf`.
+    directories.extend(environment_paths("LD_LIBRARY_PATH"));
+    for (_, path) in elf::read_ld_so_cache(sysroot).unwrap_or_default() {
+        directories.extend(path.parent().map(Path::to_path_buf));
+    }
+    // Without a binary the architecture is unknown, so every multiarch directory is searched.
+    for architecture in [
+        Architecture::Aarch64,
+        Architecture::Arm,
+        Architecture::I386,
+        Architecture::PowerPc64,
+        Architecture::Riscv64,
+        Architecture::S390x,
+        Architecture::X86_64,
+    ] {
+        directories.extend(
+            default_elf_directories(architecture)
+                .into_iter()
+                .map(|directory| in_sysroot(sysroot, directory)),
+        );
+    }
+
+    // Mach-O.
+    directories.extend(environment_paths("DYLD_LIBRARY_PATH"));
+    directories.extend(macho::fallback_library_path(sysroot));
+
+    // PE.
+    let windows = match &global.windows_config {
+        Some(path) => WindowsConfig::load(path, sysroot)?,
+        None => WindowsConfig::default(),
+    };
+    directories.extend(windows.system_directory);
+    directories.extend(windows.windows_directory);
+    match windows.path {
+        Some(path) => directories.extend(path),
+        None if cfg!(windows) => directories.extend(environment_paths("PATH")),
+        None => {}
+    }
+
+    let mut unique = Vec::<PathBuf>::new();
+    for directory in directories {
+        if directory.is_dir() && !unique.contains(&directory) {
+            unique.push(directory);
+        }
+    }
+
+    Ok(unique)
+}
+
+/// Moves an absolute path into the sysroot, relative paths are kept.
+fn in_sysroot(sysroot: Option<&Path>, path: PathBuf) -> PathBuf {
+    match (sysroot, path.strip_prefix("/")) {
+        (Some(sysroot), Ok(relative)) => sysroot.join(relative),
+        _ => path,
+    }
+}
+
+/// Resolves the symbolic links of the path, like `fs::canonicalize`.
+///
+/// Below a sysroot, absolute link targets and `..` stay inside it instead of
+/// escaping to the host. Links that cannot be resolved are kept as they are.
+fn follow_links(sysroot: Option<&Path>, path: &Path) -> PathBuf {
+    // The limit of the kernel, before it reports a loop.
+    const MAX_LINKS: usize = 40;
+
+    let Some(relative) = sysroot.and_then(|sysroot| path.strip_prefix(sysroot).ok()) else {
+        return fs::canonicalize(path).unwrap_or(path.to_path_buf());
+    };
+    let sysroot = sysroot.unwrap();
+
+    // The components that are left to resolve, the next one last.
+    let mut pending = Vec::<PathBuf>::new();
+    let push = |pending: &mut Vec<PathBuf>, path: &Path| {
+        pending.extend(
+            path.components()
+                .rev()
+                .map(|component| PathBuf::from(component.as_os_str())),
+        );
+    };
+    push(&mut pending, relative);
+
+    let mut resolved = sysroot.to_path_buf();
+    let mut links = 0;
+    while let Some(component) = pending.pop() {
+        match component.components().next() {
+            Some(Component::Normal(name)) => {
+                let next = resolved.join(name);
+                let Ok(target) = fs::read_link(&next) else {
+                    resolved = next;
+                    continue;
+                };
+
+                links += 1;
+                if links > MAX_LINKS {
+                    return path.to_path_buf();
+                }
+                if target.has_root() {
+                    resolved = sysroot.to_path_buf();
+                }
+                push(&mut pending, &target);
+            }
+            Some(Component::ParentDir) if resolved != sysroot => {
+                resolved.pop();
+            }
+            _ => {}
+        }
+    }
+
+    resolved
+}
+
+/// The directories the dynamic loader searches by default.
+fn default_elf_directories(architecture: Architecture) -> Vec<PathBuf> {
+    let mut directories = Vec::<PathBuf>::new();
+
+    // Multiarch directories, as used by Debian based distributions.
+    if let Some(triplet) = elf::multiarch_triplet(architecture) {
+        directories.push(Path::new("/lib").join(triplet));
+        directories.push(Path::new("/usr/lib").join(triplet));
+    }
+
+    directories.extend(["/lib64", "/usr/lib64", "/lib", "/usr/lib"].map(PathBuf::from));
+    directories
+}
@@ -0,0 +1,81 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use super::in_sysroot;
+
+/// Describes the Windows installation that PE dependencies are searched in, read from a JSON file.
+///
+/// Relative directories are relative to the configuration file, absolute ones are taken
+/// from the sysroot.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct WindowsConfig {
+    /// The system directory, e.g. a copy of `C:\Windows\System32`.
+    pub system_directory: Option<PathBuf>,
+    /// The Windows directory, e.g. a copy of `C:\Windows`.
+    pub windows_directory: Option<PathBuf>,
+    /// The working directory of the process.
+    pub current_directory: Option<PathBuf>,
+    /// The directories of the `PATH` variable, the environment of beil is used if this is missing.
+    pub path: Option<Vec<PathBuf>>,
+    /// Whether the current directory is searched after the system directories.
+    pub safe_dll_search_mode: bool,
+    /// The DLLs that are always loaded from the system directory.
+    pub known_dlls: Vec<String>,
+}
+
+impl Default for WindowsConfig {
+    fn default() -> Self {
+        WindowsConfig {
+            system_directory: None,
+            windows_directory: None,
+            current_directory: None,
+            path: None,
+            // Enabled by default since Windows XP SP2.
+            safe_dll_search_mode: true,
+            known_dlls: Vec::new(),
+        }
+    }
+}
+
+impl WindowsConfig {
+    pub fn load(path: &Path, sysroot: Option<&Path>) -> Result<Self, Box<dyn Error>> {
+        let mut config: WindowsConfig = serde_json::from_slice(&fs::read(path)?)?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        let locate = |directory: &Path| {
+            if directory.is_absolute() {
+                in_sysroot(sysroot, directory.to_path_buf())
+            } else {
+                base.join(directory)
+            }
+        };
+        for directory in [
+            &mut config.system_directory,
+            &mut config.windows_directory,
+            &mut config.current_directory,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *directory = locate(directory);
+        }
+        for directory in config.path.iter_mut().flatten() {
+            *directory = locate(directory);
+        }
+
+        Ok(config)
+    }
+
+    /// Whether the library is a known DLL, which the loader never searches for.
+    pub fn is_known_dll(&self, library: &str) -> bool {
+        self.known_dlls
+            .iter()
+            .any(|known_dll| known_dll.eq_ignore_ascii_case(library))
+    }
+}